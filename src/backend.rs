@@ -0,0 +1,210 @@
+//! The backend module contains the outputs a frame can be written to.
+
+use std::io::{self, Write};
+
+use crate::buffer::{Buffer, Cell};
+use crate::style::{ColorDepth, Style};
+use crate::unicode;
+
+/// This trait aims to make the output of the Renderer replaceable.
+pub trait Backend {
+	/// Returns the width and the height of the screen, in chars.
+	fn size(&self) -> (u16, u16);
+	/// Writes the given cells at their position.
+	fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+	where
+		I: Iterator<Item = (u16, u16, &'a Cell)>;
+	/// Clears the whole screen.
+	fn clear(&mut self) -> io::Result<()>;
+	/// Makes sure everything written so far is displayed.
+	fn flush(&mut self) -> io::Result<()>;
+}
+
+/// A backend writing ANSI escape sequences to a terminal.
+pub struct TerminalBackend<W: Write> {
+	/// Where the escape sequences are written, usually the standard output.
+	out: W,
+	/// The width of the terminal.
+	width: u16,
+	/// The height of the terminal.
+	height: u16,
+	/// The colors the terminal can display.
+	color_depth: ColorDepth,
+}
+
+impl<W: Write> TerminalBackend<W> {
+	/// Creates a new TerminalBackend.
+	///
+	/// The colors the terminal can display are guessed from the environment.
+	///
+	/// # Parameters
+	/// - out: where the escape sequences are written.
+	/// - width: the width of the terminal, in chars.
+	/// - height: the height of the terminal, in chars.
+	pub fn new(out: W, width: u16, height: u16) -> Self {
+		Self {
+			out,
+			width,
+			height,
+			color_depth: ColorDepth::detect(),
+		}
+	}
+
+	/// Returns the colors the terminal can display.
+	pub fn color_depth(&self) -> ColorDepth {
+		self.color_depth
+	}
+
+	/// Sets the colors the terminal can display.
+	///
+	/// The other colors are replaced by the closest ones.
+	pub fn set_color_depth(&mut self, color_depth: ColorDepth) {
+		self.color_depth = color_depth;
+	}
+
+	/// Changes the size of the terminal.
+	pub fn resize(&mut self, width: u16, height: u16) {
+		self.width = width;
+		self.height = height;
+	}
+}
+
+impl<W: Write> Backend for TerminalBackend<W> {
+	/// Returns the size of the terminal.
+	fn size(&self) -> (u16, u16) {
+		(self.width, self.height)
+	}
+
+	/// Moves the cursor to each cell and writes it.
+	///
+	/// The cursor is only moved when the cells are not contiguous,
+	/// and the style is only written when it changes. The cells covered
+	/// by a wide grapheme are skipped.
+	fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+	where
+		I: Iterator<Item = (u16, u16, &'a Cell)>,
+	{
+		let mut cursor: Option<(u16, u16)> = None;
+		let mut style: Option<Style> = None;
+		for (x, y, cell) in content {
+			if cell.symbol.is_empty() {
+				continue;
+			}
+			if cursor != Some((x, y)) {
+				write!(self.out, "\x1b[{};{}H", y + 1, x + 1)?;
+			}
+			if style != Some(cell.style) {
+				write!(self.out, "{}", cell.style.sgr_for(self.color_depth))?;
+				style = Some(cell.style);
+			}
+			write!(self.out, "{}", cell.symbol)?;
+			cursor = Some((x.saturating_add(unicode::grapheme_width(&cell.symbol).max(1)), y));
+		}
+		if style.is_some() {
+			write!(self.out, "{}", Style::default().sgr())?;
+		}
+		Ok(())
+	}
+
+	/// Clears the terminal.
+	fn clear(&mut self) -> io::Result<()> {
+		write!(self.out, "{}\x1b[2J", Style::default().sgr())
+	}
+
+	/// Flushes the output.
+	fn flush(&mut self) -> io::Result<()> {
+		self.out.flush()
+	}
+}
+
+/// A backend keeping the last frame in memory, useful in tests.
+///
+/// # Examples
+/// ```
+/// use tuim::backend::TestBackend;
+/// use tuim::components::table::{Table, TableTrait};
+/// use tuim::render::Renderer;
+///
+/// let mut table = Table::new(&["Name".to_string()], vec![vec!["tuim".to_string()]]);
+/// let mut renderer = Renderer::new(TestBackend::new(8, 6));
+/// renderer.render(&mut table).unwrap();
+/// assert_eq!(renderer.backend().lines(), [
+///     "        ",
+///     " Name   ",
+///     "        ",
+///     "        ",
+///     " tuim   ",
+///     "        ",
+/// ]);
+/// ```
+pub struct TestBackend {
+	/// The last frame.
+	buffer: Buffer,
+	/// The last frame, as plain strings.
+	lines: Vec<String>,
+}
+
+impl TestBackend {
+	/// Creates a new TestBackend.
+	///
+	/// # Parameters
+	/// - width: the width of the fake screen, in chars.
+	/// - height: the height of the fake screen, in chars.
+	pub fn new(width: u16, height: u16) -> Self {
+		let buffer = Buffer::new(width, height);
+		Self {
+			lines: buffer.lines(),
+			buffer,
+		}
+	}
+
+	/// Returns the last frame.
+	pub fn buffer(&self) -> &Buffer {
+		&self.buffer
+	}
+
+	/// Returns the last frame line by line, without the styles.
+	pub fn lines(&self) -> &[String] {
+		&self.lines
+	}
+
+	/// Changes the size of the fake screen.
+	pub fn resize(&mut self, width: u16, height: u16) {
+		self.buffer = Buffer::new(width, height);
+		self.lines = self.buffer.lines();
+	}
+}
+
+impl Backend for TestBackend {
+	/// Returns the size of the fake screen.
+	fn size(&self) -> (u16, u16) {
+		(self.buffer.width(), self.buffer.height())
+	}
+
+	/// Writes the cells in the frame kept in memory.
+	fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+	where
+		I: Iterator<Item = (u16, u16, &'a Cell)>,
+	{
+		for (x, y, cell) in content {
+			if let Some(target) = self.buffer.get_mut(x, y) {
+				*target = cell.clone();
+			}
+		}
+		self.lines = self.buffer.lines();
+		Ok(())
+	}
+
+	/// Clears the frame kept in memory.
+	fn clear(&mut self) -> io::Result<()> {
+		let area = self.buffer.area();
+		self.buffer.clear(area);
+		self.lines = self.buffer.lines();
+		Ok(())
+	}
+
+	/// Does nothing, the frame is already in memory.
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
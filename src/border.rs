@@ -0,0 +1,146 @@
+//! The border module contains the sets of chars the borders are drawn with.
+
+/// The chars drawing a border, and the lines inside it.
+///
+/// A horizontal or vertical char set to a space hides the border on
+/// that axis, which then takes no space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BorderSet {
+	/// The horizontal line, e.g. ─
+	pub horizontal: char,
+	/// The vertical line, e.g. │
+	pub vertical: char,
+	/// The top left corner, e.g. ┌
+	pub top_left: char,
+	/// The top right corner, e.g. ┐
+	pub top_right: char,
+	/// The bottom left corner, e.g. └
+	pub bottom_left: char,
+	/// The bottom right corner, e.g. ┘
+	pub bottom_right: char,
+	/// The junction of a vertical line with the top border, e.g. ┬
+	pub top_tee: char,
+	/// The junction of a vertical line with the bottom border, e.g. ┴
+	pub bottom_tee: char,
+	/// The junction of a horizontal line with the left border, e.g. ├
+	pub left_tee: char,
+	/// The junction of a horizontal line with the right border, e.g. ┤
+	pub right_tee: char,
+	/// The crossing of a horizontal and a vertical line, e.g. ┼
+	pub cross: char,
+}
+
+impl BorderSet {
+	/// No border at all.
+	pub const NONE: BorderSet = BorderSet::uniform(' ', ' ', ' ');
+
+	/// A border made of ASCII chars: - | +
+	pub const ASCII: BorderSet = BorderSet::uniform('-', '|', '+');
+
+	/// A single line: ─ │ ┌ ┼
+	pub const SINGLE: BorderSet = BorderSet {
+		horizontal: '─',
+		vertical: '│',
+		top_left: '┌',
+		top_right: '┐',
+		bottom_left: '└',
+		bottom_right: '┘',
+		top_tee: '┬',
+		bottom_tee: '┴',
+		left_tee: '├',
+		right_tee: '┤',
+		cross: '┼',
+	};
+
+	/// A double line: ═ ║ ╔ ╬
+	pub const DOUBLE: BorderSet = BorderSet {
+		horizontal: '═',
+		vertical: '║',
+		top_left: '╔',
+		top_right: '╗',
+		bottom_left: '╚',
+		bottom_right: '╝',
+		top_tee: '╦',
+		bottom_tee: '╩',
+		left_tee: '╠',
+		right_tee: '╣',
+		cross: '╬',
+	};
+
+	/// A single line with rounded corners: ─ │ ╭ ┼
+	pub const ROUNDED: BorderSet = BorderSet {
+		top_left: '╭',
+		top_right: '╮',
+		bottom_left: '╰',
+		bottom_right: '╯',
+		..BorderSet::SINGLE
+	};
+
+	/// A thick line: ━ ┃ ┏ ╋
+	pub const HEAVY: BorderSet = BorderSet {
+		horizontal: '━',
+		vertical: '┃',
+		top_left: '┏',
+		top_right: '┓',
+		bottom_left: '┗',
+		bottom_right: '┛',
+		top_tee: '┳',
+		bottom_tee: '┻',
+		left_tee: '┣',
+		right_tee: '┫',
+		cross: '╋',
+	};
+
+	/// Creates a set using the same char for all the corners and junctions.
+	///
+	/// # Parameters
+	/// - horizontal: the horizontal line.
+	/// - vertical: the vertical line.
+	/// - intersect: the corners and the junctions.
+	pub const fn uniform(horizontal: char, vertical: char, intersect: char) -> Self {
+		Self {
+			horizontal,
+			vertical,
+			top_left: intersect,
+			top_right: intersect,
+			bottom_left: intersect,
+			bottom_right: intersect,
+			top_tee: intersect,
+			bottom_tee: intersect,
+			left_tee: intersect,
+			right_tee: intersect,
+			cross: intersect,
+		}
+	}
+
+	/// Returns a preset from its name: none, ascii, single, double,
+	/// rounded or heavy.
+	pub fn preset(name: &str) -> Option<Self> {
+		match name {
+			"none" => Some(BorderSet::NONE),
+			"ascii" => Some(BorderSet::ASCII),
+			"single" => Some(BorderSet::SINGLE),
+			"double" => Some(BorderSet::DOUBLE),
+			"rounded" => Some(BorderSet::ROUNDED),
+			"heavy" => Some(BorderSet::HEAVY),
+			_ => None,
+		}
+	}
+
+	/// Returns whether there is a horizontal border.
+	pub fn has_horizontal(&self) -> bool {
+		self.horizontal != ' '
+	}
+
+	/// Returns whether there is a vertical border.
+	pub fn has_vertical(&self) -> bool {
+		self.vertical != ' '
+	}
+}
+
+impl Default for BorderSet {
+	/// Returns the set without border.
+	fn default() -> Self {
+		BorderSet::NONE
+	}
+}
@@ -0,0 +1,335 @@
+//! The buffer module contains the grid of cells the UI elements are drawn into.
+
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::unicode;
+
+/// A single char on the screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+	/// The symbol displayed in the cell, a single grapheme.
+	///
+	/// A symbol taking two cells is followed by a cell with an empty
+	/// symbol, covered by it.
+	pub symbol: String,
+	/// The style of the symbol.
+	pub style: Style,
+}
+
+impl Default for Cell {
+	/// Returns an empty cell, containing a space.
+	fn default() -> Self {
+		Self {
+			symbol: " ".to_string(),
+			style: Style::default(),
+		}
+	}
+}
+
+/// Returns a grapheme as it can be displayed.
+///
+/// A grapheme with a control char, such as a line feed or an escape,
+/// would move the cursor or start an escape sequence in the terminal:
+/// the blank ones are replaced by a space, the other ones by U+FFFD.
+fn printable(grapheme: &str) -> &str {
+	if !grapheme.chars().any(char::is_control) {
+		grapheme
+	} else if grapheme.chars().all(char::is_whitespace) {
+		" "
+	} else {
+		"\u{FFFD}"
+	}
+}
+
+/// A grid of cells, covering the screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Buffer {
+	/// The width of the buffer.
+	width: u16,
+	/// The height of the buffer.
+	height: u16,
+	/// The cells, line by line.
+	cells: Vec<Cell>,
+	/// The areas the cells can be changed in, when not empty.
+	clip: Vec<Rect>,
+}
+
+impl Buffer {
+	/// Creates a new Buffer filled with empty cells.
+	pub fn new(width: u16, height: u16) -> Self {
+		Self {
+			width,
+			height,
+			cells: vec![Cell::default(); width as usize * height as usize],
+			clip: Vec::new(),
+		}
+	}
+
+	/// Returns the width of the buffer.
+	pub fn width(&self) -> u16 {
+		self.width
+	}
+
+	/// Returns the height of the buffer.
+	pub fn height(&self) -> u16 {
+		self.height
+	}
+
+	/// Returns the area covered by the buffer.
+	pub fn area(&self) -> Rect {
+		Rect::new(0, 0, self.width, self.height)
+	}
+
+	/// Returns the cell at the given position, if inside the buffer.
+	pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+		self.index(x, y).map(|index| &self.cells[index])
+	}
+
+	/// Returns the cell at the given position mutably, if inside the buffer
+	/// and inside the clip areas, if any.
+	pub fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+		if !self.in_clip(x, y) {
+			return None;
+		}
+		self.cell_mut(x, y)
+	}
+
+	/// Returns the cell at the given position mutably, even outside of
+	/// the clip areas.
+	fn cell_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+		self.index(x, y).map(move |index| &mut self.cells[index])
+	}
+
+	/// Restricts the changes to some areas, until `unclip` is called.
+	///
+	/// Only the cells inside the areas are changed, the wide graphemes
+	/// partly inside being written whole.
+	pub(crate) fn clip(&mut self, areas: Vec<Rect>) {
+		self.clip = areas;
+	}
+
+	/// Allows the changes everywhere again.
+	pub(crate) fn unclip(&mut self) {
+		self.clip.clear();
+	}
+
+	/// Returns whether a position is inside the clip areas, if any.
+	fn in_clip(&self, x: u16, y: u16) -> bool {
+		self.clip.is_empty() || self.clip.iter().any(|area| area.contains(x, y))
+	}
+
+	/// Sets the char and the style at the given position.
+	///
+	/// Positions outside of the buffer are ignored.
+	pub fn set(&mut self, x: u16, y: u16, c: char, style: Style) {
+		let mut symbol = [0; 4];
+		self.set_symbol(x, y, c.encode_utf8(&mut symbol), style);
+	}
+
+	/// Sets a grapheme and its style at the given position.
+	///
+	/// A grapheme taking two cells covers the next cell too. When it
+	/// doesn't fit before the right side of the buffer, a space is set
+	/// instead. The wide graphemes partly overwritten are replaced by spaces.
+	/// The control chars are replaced, so that they never reach the
+	/// terminal. Positions outside of the buffer are ignored.
+	///
+	/// Returns the number of cells set.
+	pub fn set_symbol(&mut self, x: u16, y: u16, symbol: &str, style: Style) -> u16 {
+		if self.index(x, y).is_none() {
+			return 0;
+		}
+		let symbol = printable(symbol);
+		let width = unicode::grapheme_width(symbol).clamp(1, 2);
+		if width == 2 && self.index(x.saturating_add(1), y).is_none() {
+			return self.set_symbol(x, y, " ", style);
+		}
+		if !(x..x + width).any(|covered| self.in_clip(covered, y)) {
+			return width;
+		}
+		for covered in x..x + width {
+			self.break_wide(covered, y);
+		}
+		if let Some(cell) = self.cell_mut(x, y) {
+			cell.symbol = symbol.to_string();
+			cell.style = style;
+		}
+		if width == 2 {
+			if let Some(cell) = self.cell_mut(x + 1, y) {
+				cell.symbol = String::new();
+				cell.style = style;
+			}
+		}
+		width
+	}
+
+	/// Replaces by spaces the wide grapheme covering a position, if any,
+	/// before the position is overwritten.
+	fn break_wide(&mut self, x: u16, y: u16) {
+		let Some(cell) = self.get(x, y) else {
+			return;
+		};
+		let start = if cell.symbol.is_empty() { x.checked_sub(1) } else { Some(x) };
+		let Some(start) = start else {
+			return;
+		};
+		let wide = self.get(start, y)
+			.is_some_and(|cell| unicode::grapheme_width(&cell.symbol) == 2);
+		let covered = self.get(start + 1, y).is_some_and(|cell| cell.symbol.is_empty());
+		if wide && covered {
+			for position in [start, start + 1] {
+				if let Some(cell) = self.cell_mut(position, y) {
+					cell.symbol = " ".to_string();
+				}
+			}
+		}
+	}
+
+	/// Prints a text on a single line.
+	///
+	/// The text is printed grapheme by grapheme, and a grapheme taking
+	/// two cells is never cut in half. The control chars are replaced,
+	/// as by `set_symbol`.
+	///
+	/// # Parameters
+	/// - x: the column of the first char.
+	/// - y: the line.
+	/// - text: the text to print.
+	/// - max_width: the maximum number of cells to print.
+	/// - style: the style of the text.
+	///
+	/// Returns the number of cells printed.
+	pub fn print(&mut self, x: u16, y: u16, text: &str, max_width: u16, style: Style) -> u16 {
+		let mut printed: u16 = 0;
+		let mut last = None;
+		for (_, grapheme) in unicode::graphemes(text) {
+			let grapheme = printable(grapheme);
+			let width = unicode::grapheme_width(grapheme);
+			if width == 0 {
+				// A combining mark without a char to combine with.
+				if let Some(cell) = last.and_then(|last| self.get_mut(last, y)) {
+					cell.symbol.push_str(grapheme);
+				}
+				continue;
+			}
+			if printed + width > max_width {
+				break;
+			}
+			let position = x.saturating_add(printed);
+			self.set_symbol(position, y, grapheme, style);
+			last = Some(position);
+			printed += width;
+		}
+		printed
+	}
+
+	/// Resets the cells of an area to empty cells.
+	///
+	/// The wide graphemes partly cleared are replaced by spaces.
+	pub fn clear(&mut self, area: Rect) {
+		for y in area.y..area.bottom().min(self.height) {
+			for x in area.x..area.right().min(self.width) {
+				self.set_symbol(x, y, " ", Style::default());
+			}
+		}
+	}
+
+	/// Fills the cells of an area with spaces in the given style.
+	pub fn fill(&mut self, area: Rect, style: Style) {
+		for y in area.y..area.bottom().min(self.height) {
+			for x in area.x..area.right().min(self.width) {
+				self.set_symbol(x, y, " ", style);
+			}
+		}
+	}
+
+	/// Adds the dim attribute to the cells of an area.
+	pub fn dim(&mut self, area: Rect) {
+		for y in area.y..area.bottom().min(self.height) {
+			for x in area.x..area.right().min(self.width) {
+				if let Some(cell) = self.get_mut(x, y) {
+					cell.style.dim = true;
+				}
+			}
+		}
+	}
+
+	/// Returns the symbols of the buffer line by line, without the styles.
+	pub fn lines(&self) -> Vec<String> {
+		if self.width == 0 {
+			return vec![String::new(); self.height as usize];
+		}
+		self.cells.chunks(self.width as usize)
+			.map(|line| line.iter().map(|cell| cell.symbol.as_str()).collect())
+			.collect()
+	}
+
+	/// Returns the cells which differ from a previous buffer, with their position.
+	///
+	/// When the sizes differ, every cell is returned.
+	pub fn diff<'a>(&'a self, previous: &Buffer) -> Vec<(u16, u16, &'a Cell)> {
+		let resized = self.width != previous.width || self.height != previous.height;
+		self.cells.iter()
+			.enumerate()
+			.filter(|(index, cell)| resized || previous.cells[*index] != **cell)
+			.map(|(index, cell)| {
+				let width = self.width as usize;
+				((index % width) as u16, (index / width) as u16, cell)
+			})
+			.collect()
+	}
+
+	/// Converts a position into an index in the cells.
+	fn index(&self, x: u16, y: u16) -> Option<usize> {
+		if x < self.width && y < self.height {
+			Some(y as usize * self.width as usize + x as usize)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn control_chars_are_replaced() {
+		let mut buffer = Buffer::new(10, 1);
+		assert_eq!(buffer.print(0, 0, "a\nb\x1b[2J", 10, Style::default()), 7);
+		assert_eq!(buffer.lines()[0], "a b\u{FFFD}[2J   ");
+		assert!(buffer.cells.iter().all(|cell| !cell.symbol.chars().any(char::is_control)));
+
+		buffer.set_symbol(0, 0, "\x07", Style::default());
+		buffer.set(1, 0, '\t', Style::default());
+		assert!(buffer.lines()[0].starts_with("\u{FFFD} "));
+	}
+
+	#[test]
+	fn combining_marks_join_the_previous_cell() {
+		let mut buffer = Buffer::new(4, 1);
+		assert_eq!(buffer.print(0, 0, "e\u{301}\r\n東", 4, Style::default()), 4);
+		assert_eq!(buffer.get(0, 0).unwrap().symbol, "e\u{301}");
+		assert_eq!(buffer.lines()[0], "e\u{301} 東");
+	}
+
+	#[test]
+	fn wide_graphemes() {
+		let mut buffer = Buffer::new(3, 1);
+		assert_eq!(buffer.print(0, 0, "東京", 3, Style::default()), 2);
+		assert_eq!(buffer.lines()[0], "東 ");
+		buffer.set(1, 0, 'x', Style::default());
+		assert_eq!(buffer.lines()[0], " x ");
+		buffer.set_symbol(2, 0, "京", Style::default());
+		assert_eq!(buffer.get(2, 0).unwrap().symbol, " ");
+	}
+
+	#[test]
+	fn wide_graphemes_partly_clipped_are_written_whole() {
+		let mut buffer = Buffer::new(6, 1);
+		buffer.clip(vec![Rect::new(1, 0, 2, 1)]);
+		assert_eq!(buffer.print(0, 0, "東京x", 6, Style::default()), 5);
+		buffer.unclip();
+		assert_eq!(buffer.lines()[0], "東京  ");
+	}
+}
@@ -0,0 +1,559 @@
+//! This module contains the builders of the UI elements.
+//!
+//! A builder sets the properties of an element one after the other, then
+//! checks them all when the element is built:
+//!
+//! ```
+//! use tuim::border::BorderSet;
+//! use tuim::components::Size;
+//! use tuim::components::table::Table;
+//!
+//! let table = Table::builder()
+//!     .headers(&["Job", "State"])
+//!     .rows(vec![vec!["backup".to_string(), "done".to_string()]])
+//!     .border(BorderSet::ROUNDED)
+//!     .padding(1, 2)
+//!     .width(Size::Percents(50))
+//!     .title("Jobs")
+//!     .build();
+//! assert!(table.is_ok());
+//! ```
+
+use super::table::column::Column;
+use super::table::source::TableSource;
+use super::table::{Table, TableTrait};
+use super::{Container, Layout, Position, Size, UIElement};
+use crate::border::BorderSet;
+use crate::layout::{Align, Sides};
+use crate::style::Style;
+use crate::Error;
+
+/// The properties shared by all the UI elements.
+///
+/// Only the properties which have been given are applied to the element.
+#[derive(Default)]
+struct Common {
+	/// The z index.
+	z_index: Option<u8>,
+	/// The title.
+	title: Option<String>,
+	/// Where the title is placed on the top border.
+	title_align: Option<Align>,
+	/// The footer.
+	footer: Option<String>,
+	/// Where the footer is placed on the bottom border.
+	footer_align: Option<Align>,
+	/// How the element is positioned.
+	position: Option<Position>,
+	/// The offset of the element.
+	offset: Option<(u16, u16)>,
+	/// The width.
+	width: Option<Size>,
+	/// The height.
+	height: Option<Size>,
+	/// The chars the border is drawn with.
+	border: Option<BorderSet>,
+	/// The sides the border is drawn on.
+	border_sides: Option<Sides<bool>>,
+	/// The space between the border and the content.
+	padding: Option<Sides<u16>>,
+	/// The space around the border.
+	margin: Option<Sides<u16>>,
+	/// The style of the element.
+	style: Option<Style>,
+	/// The style of the border.
+	border_style: Option<Style>,
+	/// The style of the title and of the footer.
+	title_style: Option<Style>,
+}
+
+impl Common {
+	/// Checks the properties: the sizes in percents can't exceed 100.
+	fn validate(&self) -> Result<(), Error> {
+		for (key, size) in [("width", &self.width), ("height", &self.height)] {
+			if let Some(Size::Percents(percents)) = size {
+				if *percents > 100 {
+					return Err(Error::InvalidValue {
+						key: key.to_string(),
+						message: format!("{}% is more than 100%", percents),
+					});
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Applies the given properties to an element.
+	fn apply(self, element: &mut dyn UIElement) {
+		if let Some(z_index) = self.z_index {
+			element.set_z_index(z_index);
+		}
+		if let Some(title) = self.title {
+			element.set_title(&title);
+		}
+		if let Some(align) = self.title_align {
+			element.set_title_align(align);
+		}
+		if let Some(footer) = self.footer {
+			element.set_footer(&footer);
+		}
+		if let Some(align) = self.footer_align {
+			element.set_footer_align(align);
+		}
+		if let Some(position) = self.position {
+			element.set_position(position);
+		}
+		if let Some((x, y)) = self.offset {
+			element.set_offset(x, y);
+		}
+		if let Some(width) = self.width {
+			element.set_width(width);
+		}
+		if let Some(height) = self.height {
+			element.set_height(height);
+		}
+		if let Some(border) = self.border {
+			element.set_border(border);
+		}
+		if let Some(sides) = self.border_sides {
+			element.set_border_sides(sides);
+		}
+		if let Some(padding) = self.padding {
+			element.set_padding(padding);
+		}
+		if let Some(margin) = self.margin {
+			element.set_margin(margin);
+		}
+		if let Some(style) = self.style {
+			element.set_style(style);
+		}
+		if let Some(style) = self.border_style {
+			element.set_border_style(style);
+		}
+		if let Some(style) = self.title_style {
+			element.set_title_style(style);
+		}
+	}
+}
+
+/// Adds the methods setting the properties shared by all the UI elements
+/// to a builder having a `common` field.
+macro_rules! common_methods {
+	() => {
+		/// Sets the z index.
+		pub fn z_index(mut self, z_index: u8) -> Self {
+			self.common.z_index = Some(z_index);
+			self
+		}
+
+		/// Sets the title.
+		pub fn title(mut self, title: &str) -> Self {
+			self.common.title = Some(title.to_string());
+			self
+		}
+
+		/// Sets where the title is placed on the top border.
+		pub fn title_align(mut self, align: Align) -> Self {
+			self.common.title_align = Some(align);
+			self
+		}
+
+		/// Sets the footer.
+		pub fn footer(mut self, footer: &str) -> Self {
+			self.common.footer = Some(footer.to_string());
+			self
+		}
+
+		/// Sets where the footer is placed on the bottom border.
+		pub fn footer_align(mut self, align: Align) -> Self {
+			self.common.footer_align = Some(align);
+			self
+		}
+
+		/// Sets how the element is positioned.
+		pub fn position(mut self, position: Position) -> Self {
+			self.common.position = Some(position);
+			self
+		}
+
+		/// Sets the offset of the element.
+		pub fn offset(mut self, x: u16, y: u16) -> Self {
+			self.common.offset = Some((x, y));
+			self
+		}
+
+		/// Sets the width. A size in percents can't exceed 100.
+		pub fn width(mut self, width: Size) -> Self {
+			self.common.width = Some(width);
+			self
+		}
+
+		/// Sets the height. A size in percents can't exceed 100.
+		pub fn height(mut self, height: Size) -> Self {
+			self.common.height = Some(height);
+			self
+		}
+
+		/// Sets the chars the border is drawn with.
+		pub fn border(mut self, border: BorderSet) -> Self {
+			self.common.border = Some(border);
+			self
+		}
+
+		/// Sets the sides the border is drawn on.
+		pub fn border_sides(mut self, sides: Sides<bool>) -> Self {
+			self.common.border_sides = Some(sides);
+			self
+		}
+
+		/// Sets the space between the border and the content, on the top
+		/// and the bottom, and on the left and the right.
+		pub fn padding(self, vertical: u16, horizontal: u16) -> Self {
+			self.padding_sides(Sides::symmetric(vertical, horizontal))
+		}
+
+		/// Sets the space between the border and the content, on each side.
+		pub fn padding_sides(mut self, padding: Sides<u16>) -> Self {
+			self.common.padding = Some(padding);
+			self
+		}
+
+		/// Sets the space around the border, on the top and the bottom, and
+		/// on the left and the right.
+		pub fn margin(self, vertical: u16, horizontal: u16) -> Self {
+			self.margin_sides(Sides::symmetric(vertical, horizontal))
+		}
+
+		/// Sets the space around the border, on each side.
+		pub fn margin_sides(mut self, margin: Sides<u16>) -> Self {
+			self.common.margin = Some(margin);
+			self
+		}
+
+		/// Sets the style of the element.
+		pub fn style(mut self, style: Style) -> Self {
+			self.common.style = Some(style);
+			self
+		}
+
+		/// Sets the style of the border.
+		pub fn border_style(mut self, style: Style) -> Self {
+			self.common.border_style = Some(style);
+			self
+		}
+
+		/// Sets the style of the title and of the footer.
+		pub fn title_style(mut self, style: Style) -> Self {
+			self.common.title_style = Some(style);
+			self
+		}
+	};
+}
+
+/// Builds a Table, see `Table::builder`.
+///
+/// The lines are given either by headers and rows, or by a source.
+#[derive(Default)]
+pub struct TableBuilder {
+	/// The properties shared by all the UI elements.
+	common: Common,
+	/// The line of headers.
+	headers: Vec<String>,
+	/// The lines of data.
+	rows: Vec<Vec<String>>,
+	/// The source of the lines, instead of the headers and the rows.
+	source: Option<Box<dyn TableSource>>,
+	/// How the columns are displayed.
+	columns: Option<Vec<Column>>,
+	/// The number of items displayed by page.
+	items_by_page: Option<u32>,
+	/// The space around the text of each cell.
+	cell_padding: Option<Sides<u16>>,
+	/// The style of the headers.
+	header_style: Option<Style>,
+	/// The style of the lines of data.
+	row_style: Option<Style>,
+	/// The style of every other line of data.
+	alt_row_style: Option<Style>,
+	/// The style of the selection.
+	selected_style: Option<Style>,
+}
+
+impl TableBuilder {
+	/// Creates a new TableBuilder, without any line.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	common_methods!();
+
+	/// Sets the line of headers.
+	pub fn headers<S: AsRef<str>>(mut self, headers: &[S]) -> Self {
+		self.headers = headers.iter().map(|header| header.as_ref().to_string()).collect();
+		self
+	}
+
+	/// Sets the lines of data. Each of them must have as many cells as
+	/// there are headers.
+	pub fn rows(mut self, rows: Vec<Vec<String>>) -> Self {
+		self.rows = rows;
+		self
+	}
+
+	/// Sets the source the lines are pulled from, instead of the headers
+	/// and the rows.
+	pub fn source(mut self, source: Box<dyn TableSource>) -> Self {
+		self.source = Some(source);
+		self
+	}
+
+	/// Sets how the columns are displayed. There must be one column by
+	/// header, their headers being replaced by the line of headers.
+	pub fn columns(mut self, columns: Vec<Column>) -> Self {
+		self.columns = Some(columns);
+		self
+	}
+
+	/// Sets the number of items displayed by page, which can't be zero.
+	pub fn items_by_page(mut self, items_by_page: u32) -> Self {
+		self.items_by_page = Some(items_by_page);
+		self
+	}
+
+	/// Sets the space around the text of each cell, on the top and the
+	/// bottom, and on the left and the right.
+	pub fn cell_padding(self, vertical: u16, horizontal: u16) -> Self {
+		self.cell_padding_sides(Sides::symmetric(vertical, horizontal))
+	}
+
+	/// Sets the space around the text of each cell, on each side.
+	pub fn cell_padding_sides(mut self, cell_padding: Sides<u16>) -> Self {
+		self.cell_padding = Some(cell_padding);
+		self
+	}
+
+	/// Sets the style of the headers.
+	pub fn header_style(mut self, style: Style) -> Self {
+		self.header_style = Some(style);
+		self
+	}
+
+	/// Sets the style of the lines of data.
+	pub fn row_style(mut self, style: Style) -> Self {
+		self.row_style = Some(style);
+		self
+	}
+
+	/// Sets the style of every other line of data.
+	pub fn alt_row_style(mut self, style: Style) -> Self {
+		self.alt_row_style = Some(style);
+		self
+	}
+
+	/// Sets the style of the selected line, or of the selected cell.
+	pub fn selected_style(mut self, style: Style) -> Self {
+		self.selected_style = Some(style);
+		self
+	}
+
+	/// Builds the Table.
+	///
+	/// Returns an error when a line of data doesn't have as many cells as
+	/// there are headers, when both a source and lines are given, when
+	/// the columns don't match the headers, when there are zero items by
+	/// page, or when a size exceeds 100%.
+	pub fn build(self) -> Result<Table, Error> {
+		self.common.validate()?;
+		let mut table = match self.source {
+			Some(_) if !self.headers.is_empty() || !self.rows.is_empty() => {
+				return Err(Error::InvalidValue {
+					key: "source".to_string(),
+					message: "give either a source, or headers and rows".to_string(),
+				});
+			},
+			Some(source) => Table::with_source(source),
+			None => Table::try_new(&self.headers, self.rows)?,
+		};
+		if let Some(columns) = self.columns {
+			if columns.len() != table.columns().len() {
+				return Err(Error::InvalidValue {
+					key: "columns".to_string(),
+					message: format!("{} columns for {} headers", columns.len(), table.columns().len()),
+				});
+			}
+			table.set_columns(columns);
+		}
+		if let Some(items_by_page) = self.items_by_page {
+			table.set_items_by_page(items_by_page)?;
+		}
+		if let Some(cell_padding) = self.cell_padding {
+			table.set_cell_padding(cell_padding);
+		}
+		if let Some(style) = self.header_style {
+			table.set_header_style(style);
+		}
+		if let Some(style) = self.row_style {
+			table.set_row_style(style);
+		}
+		if let Some(style) = self.alt_row_style {
+			table.set_alt_row_style(style);
+		}
+		if let Some(style) = self.selected_style {
+			table.set_selected_style(style);
+		}
+		self.common.apply(&mut table);
+		Ok(table)
+	}
+}
+
+/// Builds a Container, see `Container::builder`.
+pub struct ContainerBuilder {
+	/// The properties shared by all the UI elements.
+	common: Common,
+	/// The layout of the elements.
+	layout: Layout,
+	/// The UI elements.
+	elements: Vec<Box<dyn UIElement>>,
+	/// The index of the element displayed with Layout::Tabbed.
+	active_tab: Option<usize>,
+}
+
+impl Default for ContainerBuilder {
+	/// Returns a builder of an empty container, with Layout::Vertical.
+	fn default() -> Self {
+		Self {
+			common: Common::default(),
+			layout: Layout::Vertical,
+			elements: Vec::new(),
+			active_tab: None,
+		}
+	}
+}
+
+impl ContainerBuilder {
+	/// Creates a new ContainerBuilder, without any element, with
+	/// Layout::Vertical.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	common_methods!();
+
+	/// Sets the layout of the elements.
+	pub fn layout(mut self, layout: Layout) -> Self {
+		self.layout = layout;
+		self
+	}
+
+	/// Adds an element after the previous ones.
+	pub fn child<E: UIElement + 'static>(mut self, element: E) -> Self {
+		self.elements.push(Box::new(element));
+		self
+	}
+
+	/// Adds elements after the previous ones.
+	pub fn children(mut self, elements: Vec<Box<dyn UIElement>>) -> Self {
+		self.elements.extend(elements);
+		self
+	}
+
+	/// Sets the element displayed with Layout::Tabbed.
+	pub fn active_tab(mut self, active_tab: usize) -> Self {
+		self.active_tab = Some(active_tab);
+		self
+	}
+
+	/// Builds the Container.
+	///
+	/// Returns an error when the active tab isn't one of the elements, or
+	/// when a size exceeds 100%.
+	pub fn build(self) -> Result<Container, Error> {
+		self.common.validate()?;
+		if let Some(active_tab) = self.active_tab {
+			if active_tab >= self.elements.len() {
+				return Err(Error::InvalidValue {
+					key: "active_tab".to_string(),
+					message: format!("tab {} is out of range, there are {} elements", active_tab, self.elements.len()),
+				});
+			}
+		}
+		let mut container = Container::new(self.elements, self.layout);
+		if let Some(active_tab) = self.active_tab {
+			container.set_active_tab(active_tab);
+		}
+		self.common.apply(&mut container);
+		Ok(container)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::components::table::source::VecSource;
+
+	/// Returns the key and the message of an InvalidValue error.
+	fn invalid<T>(result: Result<T, Error>) -> (String, String) {
+		match result {
+			Err(Error::InvalidValue { key, message }) => (key, message),
+			Err(error) => panic!("expected an invalid value, got {:?}", error),
+			Ok(_) => panic!("expected an invalid value, got an element"),
+		}
+	}
+
+	/// Returns a line of strings.
+	fn line(cells: &[&str]) -> Vec<String> {
+		cells.iter().map(|cell| cell.to_string()).collect()
+	}
+
+	#[test]
+	fn percents_over_100_are_rejected() {
+		assert_eq!(
+			invalid(Table::builder().headers(&["A"]).width(Size::Percents(101)).build()),
+			("width".to_string(), "101% is more than 100%".to_string()),
+		);
+		assert_eq!(invalid(Container::builder().height(Size::Percents(150)).build()).0, "height");
+		assert!(Container::builder().width(Size::Percents(100)).height(Size::Chars(200)).build().is_ok());
+	}
+
+	#[test]
+	fn active_tab_out_of_range_is_rejected() {
+		let tabs = || Container::builder()
+			.layout(Layout::Tabbed)
+			.child(Container::new(Vec::new(), Layout::Vertical))
+			.child(Container::new(Vec::new(), Layout::Vertical));
+		assert_eq!(
+			invalid(tabs().active_tab(2).build()),
+			("active_tab".to_string(), "tab 2 is out of range, there are 2 elements".to_string()),
+		);
+		assert_eq!(invalid(Container::builder().active_tab(0).build()).0, "active_tab");
+		assert_eq!(tabs().active_tab(1).build().map(|container| container.active_tab()).ok(), Some(1));
+	}
+
+	#[test]
+	fn source_and_rows_are_exclusive() {
+		let source = || Box::new(VecSource::new(&line(&["A"]), vec![line(&["a"])]));
+		assert_eq!(
+			invalid(Table::builder().source(source()).rows(vec![line(&["b"])]).build()),
+			("source".to_string(), "give either a source, or headers and rows".to_string()),
+		);
+		assert_eq!(invalid(Table::builder().headers(&["A"]).source(source()).build()).0, "source");
+		assert!(Table::builder().source(source()).build().is_ok());
+	}
+
+	#[test]
+	fn columns_must_match_the_headers() {
+		let table = Table::builder()
+			.headers(&["A", "B"])
+			.columns(vec![Column::new("A")])
+			.build();
+		assert_eq!(invalid(table), ("columns".to_string(), "1 columns for 2 headers".to_string()));
+	}
+
+	#[test]
+	fn errors_of_the_table_are_returned() {
+		let table = Table::builder().headers(&["A", "B"]).rows(vec![line(&["a", "b"]), line(&["c"])]).build();
+		assert!(matches!(table, Err(Error::RaggedRow { row: 1, expected: 2, found: 1 })));
+		let table = Table::builder().headers(&["A"]).items_by_page(0).build();
+		assert!(matches!(table, Err(Error::ZeroItemsByPage)));
+	}
+}
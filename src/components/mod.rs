@@ -1,7 +1,16 @@
-/// The components module contains the UI components.
+//! The components module contains the UI components.
 
+pub mod builder;
+pub mod popup;
 pub mod table;
 
+use crate::layout::{self, Align, Rect, Sides};
+use crate::border::BorderSet;
+use crate::buffer::Buffer;
+use crate::event::Event;
+use crate::style::Style;
+use crate::theme::Theme;
+
 /// The UIElement trait contains methods to be implemented by all
 /// UI elements (e.g. Table)
 pub trait UIElement {
@@ -10,6 +19,10 @@ pub trait UIElement {
 	/// Elements with a greater z index are printed after
 	/// the the ones with a lower index. This way, it's possible for
 	/// an element to be printed above the others (e.g. Popup).
+	///
+	/// The z index is relative to the container of the element:
+	/// the element is printed with the sum of its z index and the
+	/// z indexes of all its containers.
 	fn z_index(&self) -> u8 {
 		0
 	}
@@ -26,6 +39,58 @@ pub trait UIElement {
 
 	/// Sets the title.
 	fn set_title(&mut self, title: &str);
+
+	/// Returns where the title is placed on the top border.
+	///
+	/// By default the title is on the left.
+	fn title_align(&self) -> Align {
+		Align::Left
+	}
+
+	/// Sets where the title is placed on the top border.
+	fn set_title_align(&mut self, align: Align);
+
+	/// Returns the footer of the UI element, drawn on the bottom border.
+	///
+	/// By default the footer is empty.
+	fn footer(&self) -> &str {
+		""
+	}
+
+	/// Sets the footer.
+	fn set_footer(&mut self, footer: &str);
+
+	/// Returns where the footer is placed on the bottom border.
+	///
+	/// By default the footer is on the left.
+	fn footer_align(&self) -> Align {
+		Align::Left
+	}
+
+	/// Sets where the footer is placed on the bottom border.
+	fn set_footer_align(&mut self, align: Align);
+
+	/// Returns how the element is positioned.
+	///
+	/// By default the element is placed by its container.
+	fn position(&self) -> Position {
+		Position::Relative
+	}
+
+	/// Sets how the element is positioned.
+	fn set_position(&mut self, position: Position);
+
+	/// Returns the offset of the element, in chars.
+	///
+	/// With Position::Absolute, the offset is from the top left corner of
+	/// the screen. With Position::Relative, the element is moved by the
+	/// offset inside its container. By default there is no offset.
+	fn offset(&self) -> (u16, u16) {
+		(0, 0)
+	}
+
+	/// Sets the offset of the element.
+	fn set_offset(&mut self, x: u16, y: u16);
 	/// Returns the width of the element.
 	fn width(&self) -> Size;
 	/// Sets the width.
@@ -38,24 +103,196 @@ pub trait UIElement {
 	fn updated(&self) -> bool;
 	/// Changes the update state of the element.
 	fn set_updated(&mut self, updated: bool);
-	/// Returns the vertical border character.
-	fn border_vertical(&self) -> char;
-	/// Returns the horizontal border character.
-	fn border_horizontal(&self) -> char;
-	/// Returns the intersection border character.
-	fn border_intersect(&self) -> char;
-	/// Returns the vertical padding.
-	fn padding_vertical(&self) -> u8;
-	/// Returns the horizontal padding.
-	fn padding_horizontal(&self) -> u8;
+
+	/// Returns the chars the border is drawn with.
+	///
+	/// By default the element has no border.
+	fn border(&self) -> BorderSet {
+		BorderSet::NONE
+	}
+
+	/// Sets the chars the border is drawn with.
+	fn set_border(&mut self, border: BorderSet);
+
+	/// Returns the sides the border is drawn on.
+	///
+	/// By default the border is drawn on every side. A side without
+	/// border takes no space.
+	fn border_sides(&self) -> Sides<bool> {
+		Sides::all(true)
+	}
+
+	/// Sets the sides the border is drawn on.
+	fn set_border_sides(&mut self, sides: Sides<bool>);
+
+	/// Returns the space between the border and the content, on each side.
+	///
+	/// By default there is no padding.
+	fn padding(&self) -> Sides<u16> {
+		Sides::default()
+	}
+
+	/// Sets the space between the border and the content.
+	fn set_padding(&mut self, padding: Sides<u16>);
+
+	/// Returns the space left around the border, on each side, in the
+	/// area given to the element.
+	///
+	/// By default there is no margin.
+	fn margin(&self) -> Sides<u16> {
+		Sides::default()
+	}
+
+	/// Sets the space left around the border.
+	fn set_margin(&mut self, margin: Sides<u16>);
+
+	/// Returns the style of the element: the color of its background, and
+	/// the style its texts are based on.
+	///
+	/// By default the element has no style.
+	fn style(&self) -> Style {
+		Style::default()
+	}
+
+	/// Sets the style of the element.
+	fn set_style(&mut self, style: Style);
+
+	/// Returns the style of the border, applied over the style of the element.
+	///
+	/// By default the border has no style.
+	fn border_style(&self) -> Style {
+		Style::default()
+	}
+
+	/// Sets the style of the border.
+	fn set_border_style(&mut self, style: Style);
+
+	/// Returns the style of the title and of the footer, applied over the
+	/// style of the element.
+	///
+	/// By default the title has no style.
+	fn title_style(&self) -> Style {
+		Style::default()
+	}
+
+	/// Sets the style of the title and of the footer.
+	fn set_title_style(&mut self, style: Style);
+
+	/// Applies a theme to the element and its children.
+	///
+	/// By default the common roles of the theme are applied: base,
+	/// border and title.
+	fn apply_theme(&mut self, theme: &Theme) {
+		theme.apply_element(self, "");
+		for child in self.children_mut() {
+			child.apply_theme(theme);
+		}
+	}
+
+	/// Returns whether what is behind the element is dimmed.
+	///
+	/// By default the background is left untouched.
+	fn dims_background(&self) -> bool {
+		false
+	}
+
+	/// Returns whether the element can be focused, to receive the keys.
+	///
+	/// By default an element can't be focused.
+	fn focusable(&self) -> bool {
+		false
+	}
+
+	/// Returns whether the element is focused.
+	fn focused(&self) -> bool {
+		false
+	}
+
+	/// Changes the focus state of the element.
+	///
+	/// By default nothing is done, since the element can't be focused.
+	fn set_focused(&mut self, _focused: bool) {}
+
+	/// Returns the layout of the children, for the elements having some.
+	fn layout(&self) -> Option<Layout> {
+		None
+	}
+
+	/// Returns whether a child is currently displayed.
+	///
+	/// By default all the children are displayed.
+	fn child_visible(&self, _index: usize) -> bool {
+		true
+	}
+
+	/// Handles an input event.
+	///
+	/// Returns whether the event has been handled, in which case it's
+	/// not delivered to the containers of the element.
+	/// By default no event is handled.
+	fn handle_event(&mut self, _event: &Event) -> bool {
+		false
+	}
+
+	/// Draws the content of the element in the given area.
+	///
+	/// The border and the title are drawn by the renderer beforehand,
+	/// the area only covers what is inside the border and the padding.
+	/// By default nothing is drawn.
+	fn render(&self, _area: Rect, _buffer: &mut Buffer) {}
+
+	/// Returns the children of the element.
+	///
+	/// By default an element has no children.
+	fn children(&self) -> &[Box<dyn UIElement>] {
+		&[]
+	}
+
+	/// Returns the children of the element mutably.
+	fn children_mut(&mut self) -> &mut [Box<dyn UIElement>] {
+		&mut []
+	}
+
+	/// Computes the area of each child, in the same order as `children`.
+	///
+	/// # Parameters
+	/// - area: the area inside the border and the padding of the element.
+	fn child_areas(&self, _area: Rect) -> Vec<Rect> {
+		Vec::new()
+	}
+}
+
+/// Returns the element at the end of a path of children indexes.
+///
+/// An empty path designates the root.
+pub fn element_at_path<'a>(root: &'a dyn UIElement, path: &[usize]) -> Option<&'a dyn UIElement> {
+	match path.split_first() {
+		None => Some(root),
+		Some((index, rest)) => element_at_path(root.children().get(*index)?.as_ref(), rest),
+	}
+}
+
+/// Returns the element at the end of a path of children indexes, mutably.
+///
+/// An empty path designates the root.
+pub fn element_at_path_mut<'a>(root: &'a mut dyn UIElement, path: &[usize]) -> Option<&'a mut dyn UIElement> {
+	match path.split_first() {
+		None => Some(root),
+		Some((index, rest)) => element_at_path_mut(root.children_mut().get_mut(*index)?.as_mut(), rest),
+	}
 }
 
 /// Position specifies the type of positioning used for an element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Position {
 	/// The element is placed relative to the screen.
+	/// It doesn't take any space in its container.
 	Absolute,
 	/// The element is placed relative to the container element.
 	Relative,
+	/// The element is centred on the screen, the offset being ignored.
+	/// It doesn't take any space in its container.
+	Centered,
 }
 
 /// Size represents a size with a unit.
@@ -64,7 +301,8 @@ pub enum Size {
 	/// Compute the size automatically, based on the size of the siblings.
 	/// The default is to share the space with siblings elements.
 	Auto,
-	/// A size, given in chars.
+	/// A size, given in cells of the screen: a wide char, such as a
+	/// CJK char or an emoji, takes two cells.
 	Chars(u8),
 	/// A size, given in percentage.
 	Percents(u8),
@@ -82,6 +320,8 @@ pub enum Layout {
 	/// Top to bottom.
 	Vertical,
 	/// Tabbed
+	/// A tab bar built from the titles of the elements, and only the
+	/// active element below it.
 	Tabbed,
 }
 
@@ -91,31 +331,44 @@ pub struct Container {
 	z_index: u8,
 	/// The title of the container.
 	title: String,
+	/// Where the title is placed on the top border.
+	title_align: Align,
+	/// The footer of the container.
+	footer: String,
+	/// Where the footer is placed on the bottom border.
+	footer_align: Align,
+	/// How the container is positioned.
+	position: Position,
+	/// The offset of the container, in chars.
+	offset: (u16, u16),
 	/// The width of the container.
 	width: Size,
 	/// The height of the container.
 	height: Size,
 	/// Whether the container has been updated.
 	updated: bool,
-	/// The character to display in vertical border.
-	/// Example: |
-	border_vertical: char,
-	/// The character to display in horizontal border.
-	/// Example: -
-	border_horizontal: char,
-	/// The character to display in border intersection.
-	/// Example: +
-	border_intersect: char,
-	/// The vertical space between the border and the text inside the table.
-	padding_vertical: u8,
-	/// The horizontal space between the border and the text inside the table.
-	padding_horizontal: u8,
+	/// The style of the container: its background and the base of its texts.
+	style: Style,
+	/// The style of the border.
+	border_style: Style,
+	/// The style of the title.
+	title_style: Style,
+	/// The chars the border is drawn with.
+	border: BorderSet,
+	/// The sides the border is drawn on.
+	border_sides: Sides<bool>,
+	/// The space between the border and the elements.
+	padding: Sides<u16>,
+	/// The space around the border.
+	margin: Sides<u16>,
 	/// The layout.
 	///
 	/// The elements will be displayed using this layout.
 	pub layout: Layout,
 	/// The UI elements to display.
 	pub elements: Vec<Box<dyn UIElement>>,
+	/// The index of the element displayed with Layout::Tabbed.
+	active_tab: usize,
 }
 
 impl Container {
@@ -128,16 +381,74 @@ impl Container {
 		Self {
 			z_index: 0,
 			title: "".to_string(),
+			title_align: Align::Left,
+			footer: String::new(),
+			footer_align: Align::Left,
+			position: Position::Relative,
+			offset: (0, 0),
 			width: Size::Auto,
 			height: Size::Auto,
 			updated: true,
-			border_vertical: ' ',
-			border_horizontal: ' ',
-			border_intersect: ' ',
-			padding_vertical: 0,
-			padding_horizontal: 0,
-			elements: elements,
-			layout: layout.clone()
+			style: Style::default(),
+			border_style: Style::default(),
+			title_style: Style::default(),
+			border: BorderSet::NONE,
+			border_sides: Sides::all(true),
+			padding: Sides::default(),
+			margin: Sides::default(),
+			elements,
+			layout: layout.clone(),
+			active_tab: 0,
+		}
+	}
+
+	/// Returns a builder, to set the properties of a Container one after
+	/// the other.
+	pub fn builder() -> builder::ContainerBuilder {
+		builder::ContainerBuilder::new()
+	}
+
+	/// Returns the index of the active tab.
+	///
+	/// Only meaningful with Layout::Tabbed.
+	pub fn active_tab(&self) -> usize {
+		self.active_tab
+	}
+
+	/// Sets the active tab.
+	///
+	/// Indexes out of the elements are ignored.
+	pub fn set_active_tab(&mut self, active_tab: usize) {
+		if active_tab < self.elements.len() && active_tab != self.active_tab {
+			self.active_tab = active_tab;
+			self.updated = true;
+		}
+	}
+
+	/// Activates the tab after the active one, going back to the first
+	/// one after the last.
+	pub fn next_tab(&mut self) {
+		if !self.elements.is_empty() {
+			self.set_active_tab((self.active_tab + 1) % self.elements.len());
+		}
+	}
+
+	/// Activates the tab before the active one, going to the last
+	/// one before the first.
+	pub fn previous_tab(&mut self) {
+		if !self.elements.is_empty() {
+			let count = self.elements.len();
+			self.set_active_tab((self.active_tab + count - 1) % count);
+		}
+	}
+
+	/// Returns the label of a tab in the tab bar.
+	///
+	/// The title of the element is used, or its position when it has none.
+	fn tab_label(&self, index: usize) -> String {
+		match self.elements[index].title() {
+			"" => format!(" {} ", index + 1),
+			title => format!(" {} ", title),
 		}
 	}
 }
@@ -150,9 +461,11 @@ impl UIElement for Container {
 
 	/// Sets the z index.
 	///
-	/// TODO: set the z index on all elements?
+	/// The z indexes of the elements are relative to the container's
+	/// one, so they are raised along with the container.
 	fn set_z_index(&mut self, z_index: u8) {
 		self.z_index = z_index;
+		self.updated = true;
 	}
 
 	/// Returns the title of the table.
@@ -163,6 +476,62 @@ impl UIElement for Container {
 	/// Sets the title.
 	fn set_title(&mut self, title: &str) {
 		self.title = title.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the title is placed on the top border.
+	fn title_align(&self) -> Align {
+		self.title_align
+	}
+
+	/// Sets where the title is placed on the top border.
+	fn set_title_align(&mut self, align: Align) {
+		self.title_align = align;
+		self.updated = true;
+	}
+
+	/// Returns the footer of the container.
+	fn footer(&self) -> &str {
+		self.footer.as_str()
+	}
+
+	/// Sets the footer.
+	fn set_footer(&mut self, footer: &str) {
+		self.footer = footer.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the footer is placed on the bottom border.
+	fn footer_align(&self) -> Align {
+		self.footer_align
+	}
+
+	/// Sets where the footer is placed on the bottom border.
+	fn set_footer_align(&mut self, align: Align) {
+		self.footer_align = align;
+		self.updated = true;
+	}
+
+	/// Returns how the container is positioned.
+	fn position(&self) -> Position {
+		self.position
+	}
+
+	/// Sets how the container is positioned.
+	fn set_position(&mut self, position: Position) {
+		self.position = position;
+		self.updated = true;
+	}
+
+	/// Returns the offset of the container.
+	fn offset(&self) -> (u16, u16) {
+		self.offset
+	}
+
+	/// Sets the offset of the container.
+	fn set_offset(&mut self, x: u16, y: u16) {
+		self.offset = (x, y);
+		self.updated = true;
 	}
 
 	/// Returns the width of the table.
@@ -173,6 +542,7 @@ impl UIElement for Container {
 	/// Sets the width.
 	fn set_width(&mut self, width: Size) {
 		self.width = width.clone();
+		self.updated = true;
 	}
 
 	/// Returns the height of the table.
@@ -183,6 +553,7 @@ impl UIElement for Container {
 	/// Sets the height.
 	fn set_height(&mut self, height: Size) {
 		self.height = height.clone();
+		self.updated = true;
 	}
 
 	/// Returns whether the table has been updated and
@@ -196,24 +567,179 @@ impl UIElement for Container {
 		self.updated = updated;
 	}
 
-	/// Returns the vertical border character.
-	fn border_vertical(&self) -> char {
-		self.border_vertical
+	/// Returns the chars the border is drawn with.
+	fn border(&self) -> BorderSet {
+		self.border
 	}
-	/// Returns the horizontal border character.
-	fn border_horizontal(&self) -> char {
-		self.border_horizontal
+
+	/// Sets the chars the border is drawn with.
+	fn set_border(&mut self, border: BorderSet) {
+		self.border = border;
+		self.updated = true;
 	}
-	/// Returns the intersection border character.
-	fn border_intersect(&self) -> char {
-		self.border_intersect
+
+	/// Returns the sides the border is drawn on.
+	fn border_sides(&self) -> Sides<bool> {
+		self.border_sides
 	}
-	/// Returns the vertical padding.
-	fn padding_vertical(&self) -> u8 {
-		self.padding_vertical
+
+	/// Sets the sides the border is drawn on.
+	fn set_border_sides(&mut self, sides: Sides<bool>) {
+		self.border_sides = sides;
+		self.updated = true;
 	}
-	/// Returns the horizontal padding.
-	fn padding_horizontal(&self) -> u8 {
-		self.padding_horizontal
+
+	/// Returns the space between the border and the elements.
+	fn padding(&self) -> Sides<u16> {
+		self.padding
+	}
+
+	/// Sets the space between the border and the elements.
+	fn set_padding(&mut self, padding: Sides<u16>) {
+		self.padding = padding;
+		self.updated = true;
+	}
+
+	/// Returns the space around the border.
+	fn margin(&self) -> Sides<u16> {
+		self.margin
+	}
+
+	/// Sets the space around the border.
+	fn set_margin(&mut self, margin: Sides<u16>) {
+		self.margin = margin;
+		self.updated = true;
+	}
+
+	/// Returns the style of the container.
+	fn style(&self) -> Style {
+		self.style
+	}
+
+	/// Sets the style of the container.
+	fn set_style(&mut self, style: Style) {
+		self.style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the border.
+	fn border_style(&self) -> Style {
+		self.border_style
+	}
+
+	/// Sets the style of the border.
+	fn set_border_style(&mut self, style: Style) {
+		self.border_style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the title.
+	fn title_style(&self) -> Style {
+		self.title_style
+	}
+
+	/// Sets the style of the title.
+	fn set_title_style(&mut self, style: Style) {
+		self.title_style = style;
+		self.updated = true;
+	}
+
+	/// Applies the roles of a theme for the containers, e.g.
+	/// container.border, then the theme to the elements.
+	fn apply_theme(&mut self, theme: &Theme) {
+		theme.apply_element(self, "container");
+		for element in &mut self.elements {
+			element.apply_theme(theme);
+		}
+	}
+
+	/// Returns the elements of the container.
+	fn children(&self) -> &[Box<dyn UIElement>] {
+		&self.elements
+	}
+
+	/// Returns the elements of the container mutably.
+	fn children_mut(&mut self) -> &mut [Box<dyn UIElement>] {
+		&mut self.elements
+	}
+
+	/// Returns the layout of the container.
+	fn layout(&self) -> Option<Layout> {
+		Some(self.layout.clone())
+	}
+
+	/// Returns whether an element is displayed.
+	///
+	/// With Layout::Tabbed, only the active tab is displayed.
+	fn child_visible(&self, index: usize) -> bool {
+		match self.layout {
+			Layout::Tabbed => index == self.active_tab,
+			_ => true,
+		}
+	}
+
+	/// Draws the tab bar with Layout::Tabbed, in the style of the
+	/// container, the active tab being reversed.
+	fn render(&self, area: Rect, buffer: &mut Buffer) {
+		let Layout::Tabbed = self.layout else {
+			return;
+		};
+		if area.is_empty() {
+			return;
+		}
+		let mut x = area.x;
+		for index in 0..self.elements.len() {
+			if x >= area.right() {
+				break;
+			}
+			let style = if index == self.active_tab { self.style.reverse() } else { self.style };
+			x += buffer.print(x, area.y, &self.tab_label(index), area.right() - x, style);
+		}
+	}
+
+	/// Splits the area between the elements, according to the layout.
+	///
+	/// With Layout::Tabbed, the active element is given the area below
+	/// the tab bar, and the other ones an empty area.
+	/// The elements with Position::Absolute are left out of the layout,
+	/// they are given an empty area and placed by the renderer.
+	fn child_areas(&self, area: Rect) -> Vec<Rect> {
+		if let Layout::Tabbed = self.layout {
+			let below = Rect::new(area.x, area.y.saturating_add(1), area.width, area.height.saturating_sub(1));
+			return (0..self.elements.len())
+				.map(|index| if index == self.active_tab { below } else { Rect::new(area.x, area.y, 0, 0) })
+				.collect();
+		}
+		let in_flow = |element: &dyn UIElement| element.position() == Position::Relative;
+		let sizes: Vec<(Size, Size)> = self.elements.iter()
+			.filter(|element| in_flow(element.as_ref()))
+			.map(|element| (element.width(), element.height()))
+			.collect();
+		let mut areas = layout::split(area, &self.layout, &sizes).into_iter();
+		self.elements.iter()
+			.map(|element| if in_flow(element.as_ref()) { areas.next().unwrap_or_default() } else { Rect::default() })
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::style::Color;
+
+	#[test]
+	fn containers_use_their_roles_of_the_theme() {
+		let mut theme = Theme::new();
+		theme.set_style("base", Style::new().background(Color::Blue));
+		theme.set_style("container.base", Style::new().background(Color::Red));
+		theme.set_border("container.border", BorderSet::DOUBLE);
+		let inner = Container::new(Vec::new(), Layout::Vertical);
+		let mut root = Container::new(vec![Box::new(inner)], Layout::Vertical);
+
+		theme.apply(&mut root);
+		assert_eq!(root.style(), Style::new().background(Color::Red));
+		assert_eq!(root.border(), BorderSet::DOUBLE);
+		assert_eq!(root.children()[0].style(), Style::new().background(Color::Red));
+		assert_eq!(root.children()[0].border(), BorderSet::DOUBLE);
 	}
 }
@@ -0,0 +1,318 @@
+//! This module contains the definition of the Popup UI element.
+
+use super::{UIElement, Position, Size};
+use crate::border::BorderSet;
+use crate::layout::{Align, Rect, Sides};
+use crate::style::Style;
+use crate::theme::Theme;
+
+/// Represents the UI element Popup.
+///
+/// A popup displays an element centred over the rest of the UI.
+pub struct Popup {
+	/// The z index.
+	z_index: u8,
+	/// The element displayed in the popup.
+	content: Box<dyn UIElement>,
+	/// Whether what is behind the popup is dimmed.
+	dim: bool,
+	/// The chars the border is drawn with.
+	border: BorderSet,
+	/// The sides the border is drawn on.
+	border_sides: Sides<bool>,
+	/// The space between the border and the content.
+	padding: Sides<u16>,
+	/// The space around the border.
+	margin: Sides<u16>,
+	/// The title of the popup.
+	title: String,
+	/// Where the title is placed on the top border.
+	title_align: Align,
+	/// The footer of the popup.
+	footer: String,
+	/// Where the footer is placed on the bottom border.
+	footer_align: Align,
+	/// How the popup is positioned.
+	position: Position,
+	/// The offset of the popup, in chars.
+	offset: (u16, u16),
+	/// The width of the popup.
+	width: Size,
+	/// The height of the popup.
+	height: Size,
+	/// Whether the popup has been updated.
+	updated: bool,
+	/// The style of the popup: its background and the base of its texts.
+	style: Style,
+	/// The style of the border.
+	border_style: Style,
+	/// The style of the title.
+	title_style: Style,
+}
+
+impl Popup {
+	/// Creates a new Popup, centred on the screen.
+	///
+	/// # Parameters
+	/// - content: the element displayed in the popup. The value is moved, to avoid cloning.
+	pub fn new(content: Box<dyn UIElement>) -> Self {
+		Self {
+			z_index: 1,
+			content,
+			dim: false,
+			border: BorderSet::ASCII,
+			border_sides: Sides::all(true),
+			padding: Sides::symmetric(0, 1),
+			margin: Sides::default(),
+			title: String::new(),
+			title_align: Align::Left,
+			footer: String::new(),
+			footer_align: Align::Left,
+			position: Position::Centered,
+			offset: (0, 0),
+			width: Size::Percents(50),
+			height: Size::Percents(50),
+			updated: true,
+			style: Style::default(),
+			border_style: Style::default(),
+			title_style: Style::default(),
+		}
+	}
+
+	/// Returns the element displayed in the popup.
+	pub fn content(&self) -> &dyn UIElement {
+		self.content.as_ref()
+	}
+
+	/// Returns the element displayed in the popup mutably.
+	pub fn content_mut(&mut self) -> &mut dyn UIElement {
+		self.content.as_mut()
+	}
+
+	/// Returns whether what is behind the popup is dimmed.
+	pub fn dim(&self) -> bool {
+		self.dim
+	}
+
+	/// Sets whether what is behind the popup is dimmed.
+	pub fn set_dim(&mut self, dim: bool) {
+		self.dim = dim;
+		self.updated = true;
+	}
+}
+
+impl UIElement for Popup {
+	/// Returns the z index.
+	fn z_index(&self) -> u8 {
+		self.z_index
+	}
+
+	/// Sets the z index.
+	fn set_z_index(&mut self, z_index: u8) {
+		self.z_index = z_index;
+		self.updated = true;
+	}
+
+	/// Returns the title of the popup.
+	fn title(&self) -> &str {
+		self.title.as_str()
+	}
+
+	/// Sets the title.
+	fn set_title(&mut self, title: &str) {
+		self.title = title.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the title is placed on the top border.
+	fn title_align(&self) -> Align {
+		self.title_align
+	}
+
+	/// Sets where the title is placed on the top border.
+	fn set_title_align(&mut self, align: Align) {
+		self.title_align = align;
+		self.updated = true;
+	}
+
+	/// Returns the footer of the popup.
+	fn footer(&self) -> &str {
+		self.footer.as_str()
+	}
+
+	/// Sets the footer.
+	fn set_footer(&mut self, footer: &str) {
+		self.footer = footer.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the footer is placed on the bottom border.
+	fn footer_align(&self) -> Align {
+		self.footer_align
+	}
+
+	/// Sets where the footer is placed on the bottom border.
+	fn set_footer_align(&mut self, align: Align) {
+		self.footer_align = align;
+		self.updated = true;
+	}
+
+	/// Returns how the popup is positioned.
+	fn position(&self) -> Position {
+		self.position
+	}
+
+	/// Sets how the popup is positioned.
+	fn set_position(&mut self, position: Position) {
+		self.position = position;
+		self.updated = true;
+	}
+
+	/// Returns the offset of the popup.
+	fn offset(&self) -> (u16, u16) {
+		self.offset
+	}
+
+	/// Sets the offset of the popup.
+	fn set_offset(&mut self, x: u16, y: u16) {
+		self.offset = (x, y);
+		self.updated = true;
+	}
+
+	/// Returns the width of the popup.
+	fn width(&self) -> Size {
+		self.width.clone()
+	}
+
+	/// Sets the width.
+	fn set_width(&mut self, width: Size) {
+		self.width = width.clone();
+		self.updated = true;
+	}
+
+	/// Returns the height of the popup.
+	fn height(&self) -> Size {
+		self.height.clone()
+	}
+
+	/// Sets the height.
+	fn set_height(&mut self, height: Size) {
+		self.height = height.clone();
+		self.updated = true;
+	}
+
+	/// Returns whether the popup has been updated and
+	/// needs to be redrawn.
+	fn updated(&self) -> bool {
+		self.updated
+	}
+
+	/// Changes the state of the element.
+	fn set_updated(&mut self, updated: bool) {
+		self.updated = updated;
+	}
+
+	/// Returns the chars the border is drawn with.
+	fn border(&self) -> BorderSet {
+		self.border
+	}
+
+	/// Sets the chars the border is drawn with.
+	fn set_border(&mut self, border: BorderSet) {
+		self.border = border;
+		self.updated = true;
+	}
+
+	/// Returns the sides the border is drawn on.
+	fn border_sides(&self) -> Sides<bool> {
+		self.border_sides
+	}
+
+	/// Sets the sides the border is drawn on.
+	fn set_border_sides(&mut self, sides: Sides<bool>) {
+		self.border_sides = sides;
+		self.updated = true;
+	}
+
+	/// Returns the space between the border and the content.
+	fn padding(&self) -> Sides<u16> {
+		self.padding
+	}
+
+	/// Sets the space between the border and the content.
+	fn set_padding(&mut self, padding: Sides<u16>) {
+		self.padding = padding;
+		self.updated = true;
+	}
+
+	/// Returns the space around the border.
+	fn margin(&self) -> Sides<u16> {
+		self.margin
+	}
+
+	/// Sets the space around the border.
+	fn set_margin(&mut self, margin: Sides<u16>) {
+		self.margin = margin;
+		self.updated = true;
+	}
+
+	/// Returns the style of the popup.
+	fn style(&self) -> Style {
+		self.style
+	}
+
+	/// Sets the style of the popup.
+	fn set_style(&mut self, style: Style) {
+		self.style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the border.
+	fn border_style(&self) -> Style {
+		self.border_style
+	}
+
+	/// Sets the style of the border.
+	fn set_border_style(&mut self, style: Style) {
+		self.border_style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the title.
+	fn title_style(&self) -> Style {
+		self.title_style
+	}
+
+	/// Sets the style of the title.
+	fn set_title_style(&mut self, style: Style) {
+		self.title_style = style;
+		self.updated = true;
+	}
+
+	/// Applies the roles of a theme for the popups, e.g. popup.border,
+	/// then the theme to the content.
+	fn apply_theme(&mut self, theme: &Theme) {
+		theme.apply_element(self, "popup");
+		self.content.apply_theme(theme);
+	}
+
+	/// Returns whether what is behind the popup is dimmed.
+	fn dims_background(&self) -> bool {
+		self.dim
+	}
+
+	/// Returns the content of the popup.
+	fn children(&self) -> &[Box<dyn UIElement>] {
+		std::slice::from_ref(&self.content)
+	}
+
+	/// Returns the content of the popup mutably.
+	fn children_mut(&mut self) -> &mut [Box<dyn UIElement>] {
+		std::slice::from_mut(&mut self.content)
+	}
+
+	/// Gives the whole area inside the padding to the content.
+	fn child_areas(&self, area: Rect) -> Vec<Rect> {
+		vec![area]
+	}
+}
@@ -1,6 +1,32 @@
-/// This module contains the definition of the Table UI element.
+//! This module contains the definition of the Table UI element.
 
-use super::{UIElement, Size};
+pub mod column;
+pub mod compare;
+pub mod filter;
+pub mod format;
+pub mod pattern;
+pub mod source;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::builder::TableBuilder;
+use super::{UIElement, Position, Size};
+use crate::layout::{Align, Rect, Sides};
+use crate::border::BorderSet;
+use crate::buffer::Buffer;
+use crate::event::{Event, KeyCode};
+use crate::style::Style;
+use crate::theme::Theme;
+use crate::unicode;
+use crate::Error;
+use column::{Column, ColumnWidth, Overflow};
+use filter::Filter;
+use source::{TableSource, VecSource};
+
+/// The number of lines fetched at once when scanning the source.
+const FETCH_CHUNK: usize = 1024;
 
 /// This trait aims to make the Table struct replaceable by any struct which
 /// implement it.
@@ -11,86 +37,251 @@ pub trait TableTrait {
 	/// - headers: contains the line of headers. MUST have the same length
 	///   than data.
 	/// - data: contains the lines of data. MUST have the same length than headers.
+	///
+	/// See `try_new` to check the lengths.
 	fn new(headers: &[String], data: Vec<Vec<String>>) -> Self;
+	/// Creates a new instance, checking that all the lines of data have
+	/// as many cells as there are headers.
+	///
+	/// Returns Error::RaggedRow for the first line which doesn't.
+	fn try_new(headers: &[String], data: Vec<Vec<String>>) -> Result<Self, Error>
+	where
+		Self: Sized,
+	{
+		let ragged = data.iter()
+			.enumerate()
+			.find(|(_, line)| line.len() != headers.len());
+		if let Some((row, line)) = ragged {
+			return Err(Error::RaggedRow {
+				row,
+				expected: headers.len(),
+				found: line.len(),
+			});
+		}
+		Ok(Self::new(headers, data))
+	}
 	/// Returns the line of headers.
 	fn headers(&self) -> Vec<String>;
-	/// Returns the lines of data.
-	fn data(&self) -> &Vec<Vec<String>>;
+	/// Returns the source of the lines of data.
+	fn source(&self) -> &dyn TableSource;
+	/// Returns the source of the lines of data, to change them.
+	///
+	/// The lines are fetched again only when the table is reloaded.
+	fn source_mut(&mut self) -> &mut dyn TableSource;
 	/// Returns the current page of the table.
 	fn current_page(&self) -> u32;
 	/// Sets the current page of the table.
-	fn set_current_page(&mut self, current_page: u32);
+	///
+	/// Returns Error::PageOutOfRange when the page is after the last one.
+	fn set_current_page(&mut self, current_page: u32) -> Result<(), Error>;
+	/// Returns the number of pages, at least one even without data.
+	fn page_count(&self) -> u32;
 	/// Returns the number of items displayed by page.
 	fn items_by_page(&self) -> u32;
 	/// Sets the number of items displayed by page.
-	fn set_items_by_page(&mut self, items_by_page: u32);
+	///
+	/// Returns Error::ZeroItemsByPage when it's zero.
+	fn set_items_by_page(&mut self, items_by_page: u32) -> Result<(), Error>;
+	/// Returns the index of the selected line of data, if any.
+	fn selected_row(&self) -> Option<usize>;
+	/// Selects a line of data, or none.
+	///
+	/// The current page follows the selected line.
+	fn set_selected_row(&mut self, selected_row: Option<usize>);
+	/// Returns the index of the selected column, if cells are selected.
+	fn selected_column(&self) -> Option<usize>;
+	/// Selects a column, to select a single cell of the selected line,
+	/// or none to select whole lines.
+	fn set_selected_column(&mut self, selected_column: Option<usize>);
+	/// Returns the column the data is sorted by, and the order.
+	fn sorting(&self) -> Option<(usize, SortOrder)>;
+	/// Sorts the displayed lines by a column.
+	///
+	/// The sort is stable: the lines with equal cells keep their order.
+	/// The data is left untouched, and the indexes of the lines still
+	/// designate the same lines.
+	fn sort_by(&mut self, column: usize, order: SortOrder);
+	/// Removes the sort, displaying the lines in the order of the data.
+	fn clear_sorting(&mut self);
+	/// Sorts by a column in ascending order, then in descending order,
+	/// then removes the sort, each time it's called for the column.
+	fn cycle_sorting(&mut self, column: usize) {
+		match self.sorting() {
+			Some((sorted, SortOrder::Ascending)) if sorted == column => self.sort_by(column, SortOrder::Descending),
+			Some((sorted, SortOrder::Descending)) if sorted == column => self.clear_sorting(),
+			_ => self.sort_by(column, SortOrder::Ascending),
+		}
+	}
+	/// Returns the filter narrowing the displayed lines, if any.
+	fn filter(&self) -> Option<&Filter>;
+	/// Sets the filter narrowing the displayed lines, or none to display
+	/// all of them.
+	///
+	/// The data is left untouched, the pages only count the accepted lines.
+	///
+	/// Filter::Matches uses the small engine of the pattern module, not a
+	/// full regular expression one: the backreferences, the lookarounds,
+	/// the Unicode classes such as `\p{L}`, the lazy repetitions and the
+	/// flags other than a leading `(?i)` are not supported, and are
+	/// rejected by Pattern::new.
+	fn set_filter(&mut self, filter: Option<Filter>);
+}
+
+/// The order of a sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+	/// From the smallest to the greatest.
+	Ascending,
+	/// From the greatest to the smallest.
+	Descending,
+}
+
+impl SortOrder {
+	/// Returns the other order.
+	pub fn reversed(&self) -> Self {
+		match self {
+			SortOrder::Ascending => SortOrder::Descending,
+			SortOrder::Descending => SortOrder::Ascending,
+		}
+	}
+
+	/// Returns the indicator displayed in the header of the sorted column.
+	fn indicator(&self) -> &'static str {
+		match self {
+			SortOrder::Ascending => " ▲",
+			SortOrder::Descending => " ▼",
+		}
+	}
 }
 
+/// A function comparing two cells of a column, to sort a Table.
+///
+/// See the compare module for the usual ones.
+pub type Comparator = Box<dyn Fn(&str, &str) -> Ordering>;
+
+/// A function giving the style of a line of a Table, from its index and
+/// its cells, or None to keep the style of the lines.
+pub type RowStyler = Box<dyn Fn(usize, &[String]) -> Option<Style>>;
+
+/// A function giving the style of a cell of a Table, from the index of its
+/// line, the index of its column and its text, or None to keep the style
+/// of its line.
+pub type CellStyler = Box<dyn Fn(usize, usize, &str) -> Option<Style>>;
+
+/// The function called when Enter is pressed on a line of a Table.
+pub type OnActivate = Box<dyn FnMut(usize, &[String])>;
+
 /// Represents the UI element Table.
 pub struct Table {
 	/// The z index.
 	z_index: u8,
-	/// The line of headers.
-	headers: Vec<String>,
-	/// The lines & columns contained in the table.
+	/// The columns, with the headers given by the source.
+	columns: Vec<Column>,
+	/// Provides the lines & columns contained in the table.
 	/// Doesn't include the headers.
-	data: Vec<Vec<String>>,
-	/// The character to display in vertical border.
-	/// Example: |
-	border_vertical: char,
-	/// The character to display in horizontal border.
-	/// Example: -
-	border_horizontal: char,
-	/// The character to display in border intersection.
-	/// Example: +
-	border_intersect: char,
-	/// The vertical space between the border and the text inside the table.
-	padding_vertical: u8,
-	/// The horizontal space between the border and the text inside the table.
-	padding_horizontal: u8,
+	source: Box<dyn TableSource>,
+	/// The chars the border, and the lines between the columns and below
+	/// the headers, are drawn with.
+	border: BorderSet,
+	/// The sides the border is drawn on.
+	border_sides: Sides<bool>,
+	/// The space between the border and the table.
+	padding: Sides<u16>,
+	/// The space around the border.
+	margin: Sides<u16>,
+	/// The space around the text of each cell.
+	cell_padding: Sides<u16>,
 	/// The title of the table.
 	title: String,
+	/// Where the title is placed on the top border.
+	title_align: Align,
+	/// The footer of the table.
+	footer: String,
+	/// Where the footer is placed on the bottom border.
+	footer_align: Align,
+	/// How the table is positioned.
+	position: Position,
+	/// The offset of the table, in chars.
+	offset: (u16, u16),
 	/// The width of the table.
 	width: Size,
 	/// The height of the table.
 	height: Size,
 	/// Whether the table has been updated.
 	updated: bool,
-	/// The current page in the table.
+	/// The style of the table: its background and the base of its texts.
+	style: Style,
+	/// The style of the border.
+	border_style: Style,
+	/// The style of the title.
+	title_style: Style,
+	/// The style of the headers, applied over the style of the table.
+	header_style: Style,
+	/// The style of the lines of data, applied over the style of the table.
+	row_style: Style,
+	/// The style of every other line of data, applied over row_style.
+	alt_row_style: Style,
+	/// The style of the selected line, or of the selected cell.
+	selected_style: Style,
+	/// Gives the style of each line of data, applied over row_style.
+	row_styler: Option<RowStyler>,
+	/// Gives the style of each cell of data, applied over the style of its line.
+	cell_styler: Option<CellStyler>,
+	/// Whether the table is focused.
+	focused: bool,
+	/// The current page in the table, among the displayed lines.
 	current_page: u32,
 	/// The number of items displayed by page.
-	items_by_page: u32
+	items_by_page: u32,
+	/// The index of the selected line of data.
+	selected_row: Option<usize>,
+	/// The index of the selected column, when cells are selected.
+	selected_column: Option<usize>,
+	/// Called with the index and the cells of a line when Enter is
+	/// pressed on it.
+	on_activate: Option<OnActivate>,
+	/// The column the data is sorted by, and the order.
+	sorting: Option<(usize, SortOrder)>,
+	/// The position of each line of data once sorted, by index, or
+	/// nothing when the lines aren't sorted.
+	ranks: Vec<usize>,
+	/// The comparators of the columns, by index.
+	/// The columns without comparator are sorted with compare::lexical.
+	comparators: HashMap<usize, Comparator>,
+	/// The filter narrowing the displayed lines.
+	filter: Option<Filter>,
+	/// The indexes of the displayed lines of data, in order, or None
+	/// when all the lines are displayed in the order of the data.
+	view: Option<Vec<usize>>,
+	/// The indexes and the cells of the lines of the current page.
+	page: Vec<(usize, Vec<String>)>,
+	/// The text searched with `/`, highlighted in the cells.
+	search: String,
+	/// Whether the searched text is being typed.
+	searching: bool,
+	/// The number of chars the columns with Overflow::Scroll are scrolled by.
+	horizontal_scroll: u16,
 }
 
 impl TableTrait for Table {
 	/// Create a new Table.
 	fn new(headers: &[String], data: Vec<Vec<String>>) -> Self {
-		Self {
-			z_index: 0,
-			headers: headers.to_vec(),
-			data: data.to_vec(),
-			border_vertical: ' ',
-			border_horizontal: ' ',
-			border_intersect: ' ',
-			padding_vertical: 1,
-			padding_horizontal: 1,
-			title: String::new(),
-			width: Size::Auto,
-			height: Size::Auto,
-			updated: true,
-			current_page: 0,
-			items_by_page: 20,
-		}
+		Self::with_source(Box::new(VecSource::new(headers, data)))
 	}
 
 	/// Returns the line of headers.
 	fn headers(&self) -> Vec<String> {
-		self.headers.clone()
+		self.columns.iter().map(|column| column.header.clone()).collect()
 	}
 
-	/// Returns the lines of data.
-	fn data(&self) -> &Vec<Vec<String>> {
-		&self.data
+	/// Returns the source of the lines of data.
+	fn source(&self) -> &dyn TableSource {
+		self.source.as_ref()
+	}
+
+	/// Returns the source of the lines of data, to change them.
+	fn source_mut(&mut self) -> &mut dyn TableSource {
+		self.source.as_mut()
 	}
 
 	/// Returns the current page of the table.
@@ -98,9 +289,21 @@ impl TableTrait for Table {
 		self.current_page
 	}
 
-	/// Sets the current page of the table.
-	fn set_current_page(&mut self, current_page: u32) {
-		self.current_page = current_page
+	/// Sets the current page of the table, and fetches its lines.
+	fn set_current_page(&mut self, current_page: u32) -> Result<(), Error> {
+		let page_count = self.page_count();
+		if current_page >= page_count {
+			return Err(Error::PageOutOfRange { page: current_page, page_count });
+		}
+		self.current_page = current_page;
+		self.load_page();
+		Ok(())
+	}
+
+	/// Returns the number of pages of the displayed lines.
+	fn page_count(&self) -> u32 {
+		let pages = self.visible_row_count().div_ceil(self.items_by_page as usize).max(1);
+		u32::try_from(pages).unwrap_or(u32::MAX)
 	}
 
 	/// Returns the number of items displayed by page.
@@ -108,12 +311,920 @@ impl TableTrait for Table {
 		self.items_by_page
 	}
 
-	/// Sets the number of items displayed by page.
-	fn set_items_by_page(&mut self, items_by_page: u32) {
-		self.items_by_page = items_by_page
+	/// Sets the number of items displayed by page, and fetches the lines
+	/// of the current page.
+	///
+	/// The current page is kept among the remaining pages.
+	fn set_items_by_page(&mut self, items_by_page: u32) -> Result<(), Error> {
+		if items_by_page == 0 {
+			return Err(Error::ZeroItemsByPage);
+		}
+		self.items_by_page = items_by_page;
+		self.current_page = self.current_page.min(self.page_count() - 1);
+		self.load_page();
+		Ok(())
+	}
+
+	/// Returns the index of the selected line of data.
+	fn selected_row(&self) -> Option<usize> {
+		self.selected_row
+	}
+
+	/// Selects a line of data, and displays the page containing it.
+	///
+	/// Indexes after the last line select the last line. Lines rejected
+	/// by the filter can't be selected.
+	fn set_selected_row(&mut self, selected_row: Option<usize>) {
+		let row_count = self.source.row_count();
+		let selected_row = selected_row
+			.filter(|_| row_count > 0)
+			.map(|row| row.min(row_count - 1));
+		let position = selected_row.and_then(|row| self.position_of(row));
+		if let Some(position) = position {
+			let page = (position / self.items_by_page as usize) as u32;
+			if page != self.current_page {
+				self.current_page = page;
+				self.load_page();
+			}
+		}
+		self.selected_row = selected_row.filter(|_| position.is_some());
+		self.updated = true;
+	}
+
+	/// Returns the index of the selected column.
+	fn selected_column(&self) -> Option<usize> {
+		self.selected_column
+	}
+
+	/// Selects a column, or whole lines.
+	///
+	/// Indexes after the last column select the last column.
+	fn set_selected_column(&mut self, selected_column: Option<usize>) {
+		self.selected_column = selected_column
+			.filter(|_| !self.columns.is_empty())
+			.map(|column| column.min(self.columns.len() - 1));
+		self.updated = true;
+	}
+
+	/// Returns the column the data is sorted by, and the order.
+	fn sorting(&self) -> Option<(usize, SortOrder)> {
+		self.sorting
+	}
+
+	/// Sorts the displayed lines by a column, with the comparator of the
+	/// column.
+	///
+	/// The whole source is scanned, and the order of the lines is kept
+	/// aside. The selected line stays selected, wherever it moves.
+	/// Columns out of the headers are ignored.
+	fn sort_by(&mut self, column: usize, order: SortOrder) {
+		if column >= self.columns.len() {
+			return;
+		}
+		self.sorting = Some((column, order));
+		self.sort_rows();
+		self.refresh_view();
+	}
+
+	/// Removes the sort, the selected line staying selected.
+	fn clear_sorting(&mut self) {
+		self.sorting = None;
+		self.sort_rows();
+		self.refresh_view();
+	}
+
+	/// Returns the filter narrowing the displayed lines.
+	fn filter(&self) -> Option<&Filter> {
+		self.filter.as_ref()
+	}
+
+	/// Sets the filter narrowing the displayed lines.
+	///
+	/// The selected line is unselected when the filter rejects it, and
+	/// the current page is kept among the remaining pages.
+	fn set_filter(&mut self, filter: Option<Filter>) {
+		self.filter = filter;
+		self.refresh_view();
 	}
 }
 
+impl Table {
+	/// Creates a new Table pulling its lines from a source.
+	///
+	/// Only the lines of the current page are fetched, when it changes.
+	pub fn with_source(source: Box<dyn TableSource>) -> Self {
+		let mut table = Self {
+			z_index: 0,
+			columns: source.headers().iter().map(|header| Column::new(header)).collect(),
+			source,
+			border: BorderSet::NONE,
+			border_sides: Sides::all(true),
+			padding: Sides::default(),
+			margin: Sides::default(),
+			cell_padding: Sides::all(1),
+			title: String::new(),
+			title_align: Align::Left,
+			footer: String::new(),
+			footer_align: Align::Left,
+			position: Position::Relative,
+			offset: (0, 0),
+			width: Size::Auto,
+			height: Size::Auto,
+			updated: true,
+			style: Style::default(),
+			border_style: Style::default(),
+			title_style: Style::default(),
+			header_style: Style::default(),
+			row_style: Style::default(),
+			alt_row_style: Style::default(),
+			selected_style: Style::new().reverse(),
+			row_styler: None,
+			cell_styler: None,
+			focused: false,
+			current_page: 0,
+			items_by_page: 20,
+			selected_row: None,
+			selected_column: None,
+			on_activate: None,
+			sorting: None,
+			ranks: Vec::new(),
+			comparators: HashMap::new(),
+			filter: None,
+			view: None,
+			page: Vec::new(),
+			search: String::new(),
+			searching: false,
+			horizontal_scroll: 0,
+		};
+		table.load_page();
+		table
+	}
+
+	/// Returns a builder, to set the properties of a Table one after the
+	/// other.
+	pub fn builder() -> TableBuilder {
+		TableBuilder::new()
+	}
+
+	/// Sets the function called when Enter is pressed on a line.
+	///
+	/// The function is given the index and the cells of the selected line.
+	pub fn set_on_activate<F>(&mut self, on_activate: F)
+	where
+		F: FnMut(usize, &[String]) + 'static,
+	{
+		self.on_activate = Some(Box::new(on_activate));
+	}
+
+	/// Sets the comparator used to sort a column.
+	///
+	/// The lines are sorted again when the column is the sorted one.
+	///
+	/// # Parameters
+	/// - column: the index of the column.
+	/// - comparator: compares two cells of the column, e.g. compare::numeric.
+	pub fn set_comparator<F>(&mut self, column: usize, comparator: F)
+	where
+		F: Fn(&str, &str) -> Ordering + 'static,
+	{
+		self.comparators.insert(column, Box::new(comparator));
+		if self.sorting.is_some_and(|(sorted, _)| sorted == column) {
+			self.sort_rows();
+			self.refresh_view();
+		}
+	}
+
+	/// Fetches the lines from the source again, after they changed.
+	///
+	/// The lines are sorted and filtered again, and the selected line is
+	/// unselected when it doesn't exist anymore.
+	pub fn reload(&mut self) {
+		let headers = self.source.headers();
+		self.columns.truncate(headers.len());
+		for (index, header) in headers.iter().enumerate() {
+			match self.columns.get_mut(index) {
+				Some(column) => column.header = header.clone(),
+				None => self.columns.push(Column::new(header)),
+			}
+		}
+		if self.sorting.is_some_and(|(column, _)| column >= self.columns.len()) {
+			self.sorting = None;
+		}
+		self.sort_rows();
+		self.refresh_view();
+	}
+
+	/// Returns the style of the headers.
+	pub fn header_style(&self) -> Style {
+		self.header_style
+	}
+
+	/// Sets the style of the headers, applied over the style of the table.
+	pub fn set_header_style(&mut self, header_style: Style) {
+		self.header_style = header_style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the lines of data.
+	pub fn row_style(&self) -> Style {
+		self.row_style
+	}
+
+	/// Sets the style of the lines of data, applied over the style of the table.
+	pub fn set_row_style(&mut self, row_style: Style) {
+		self.row_style = row_style;
+		self.updated = true;
+	}
+
+	/// Returns the style of every other line of data.
+	pub fn alt_row_style(&self) -> Style {
+		self.alt_row_style
+	}
+
+	/// Sets the style of every other line of data, applied over the style
+	/// of the lines, to tell the lines apart.
+	pub fn set_alt_row_style(&mut self, alt_row_style: Style) {
+		self.alt_row_style = alt_row_style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the selected line, or of the selected cell.
+	pub fn selected_style(&self) -> Style {
+		self.selected_style
+	}
+
+	/// Sets the style of the selected line, or of the selected cell,
+	/// applied over the other styles. By default the selection is reversed.
+	pub fn set_selected_style(&mut self, selected_style: Style) {
+		self.selected_style = selected_style;
+		self.updated = true;
+	}
+
+	/// Sets the function giving the style of each line of data, e.g. to
+	/// color the lines by status.
+	///
+	/// The function is given the index and the cells of the line, and
+	/// its style is applied over the style of the lines.
+	pub fn set_row_styler<F>(&mut self, row_styler: F)
+	where
+		F: Fn(usize, &[String]) -> Option<Style> + 'static,
+	{
+		self.row_styler = Some(Box::new(row_styler));
+		self.updated = true;
+	}
+
+	/// Sets the function giving the style of each cell of data.
+	///
+	/// The function is given the index of the line, the index of the
+	/// column and the text of the cell, and its style is applied over the
+	/// style of the line.
+	pub fn set_cell_styler<F>(&mut self, cell_styler: F)
+	where
+		F: Fn(usize, usize, &str) -> Option<Style> + 'static,
+	{
+		self.cell_styler = Some(Box::new(cell_styler));
+		self.updated = true;
+	}
+
+	/// Returns the space around the text of each cell.
+	pub fn cell_padding(&self) -> Sides<u16> {
+		self.cell_padding
+	}
+
+	/// Sets the space around the text of each cell, headers included.
+	///
+	/// By default there is one char on each side.
+	pub fn set_cell_padding(&mut self, cell_padding: Sides<u16>) {
+		self.cell_padding = cell_padding;
+		self.updated = true;
+	}
+
+	/// Returns the columns.
+	pub fn columns(&self) -> &[Column] {
+		&self.columns
+	}
+
+	/// Returns a column, to change how it's displayed.
+	pub fn column_mut(&mut self, column: usize) -> Option<&mut Column> {
+		self.updated = true;
+		self.columns.get_mut(column)
+	}
+
+	/// Sets the columns.
+	///
+	/// The headers given by the source are kept, the extra columns are
+	/// ignored and the missing ones keep their description.
+	pub fn set_columns(&mut self, columns: Vec<Column>) {
+		for (current, column) in self.columns.iter_mut().zip(columns) {
+			*current = Column {
+				header: current.header.clone(),
+				..column
+			};
+		}
+		self.updated = true;
+	}
+
+	/// Returns the number of chars the columns with Overflow::Scroll are
+	/// scrolled by.
+	pub fn horizontal_scroll(&self) -> u16 {
+		self.horizontal_scroll
+	}
+
+	/// Scrolls the columns with Overflow::Scroll by a number of chars.
+	///
+	/// Each column stops scrolling at the end of its texts.
+	pub fn set_horizontal_scroll(&mut self, horizontal_scroll: u16) {
+		self.horizontal_scroll = horizontal_scroll;
+		self.updated = true;
+	}
+
+	/// Returns the number of lines displayed, on all the pages.
+	pub fn visible_row_count(&self) -> usize {
+		match &self.view {
+			Some(view) => view.len(),
+			None => self.source.row_count(),
+		}
+	}
+
+	/// Returns the index of the line of data displayed at a position,
+	/// on all the pages.
+	fn row_at(&self, position: usize) -> usize {
+		match &self.view {
+			Some(view) => view[position],
+			None => position,
+		}
+	}
+
+	/// Returns the position of a displayed line of data, on all the pages.
+	fn position_of(&self, row: usize) -> Option<usize> {
+		match &self.view {
+			Some(view) if row < self.source.row_count() => {
+				view.binary_search_by_key(&self.rank(row), |row| self.rank(*row)).ok()
+			},
+			Some(_) => None,
+			None => Some(row).filter(|row| *row < self.source.row_count()),
+		}
+	}
+
+	/// Returns the position of a line of data once sorted, which is its
+	/// index when the lines aren't sorted.
+	fn rank(&self, row: usize) -> usize {
+		self.ranks.get(row).copied().unwrap_or(row)
+	}
+
+	/// Computes the position of each line of data once sorted, scanning
+	/// the whole source, or forgets them when the lines aren't sorted.
+	fn sort_rows(&mut self) {
+		self.ranks.clear();
+		let Some((column, order)) = self.sorting else {
+			return;
+		};
+		let row_count = self.source.row_count();
+		let mut cells = Vec::with_capacity(row_count);
+		for start in (0..row_count).step_by(FETCH_CHUNK) {
+			let lines = self.source.fetch(start..start.saturating_add(FETCH_CHUNK).min(row_count));
+			cells.extend(lines.into_iter().map(|mut line| {
+				if column < line.len() { line.swap_remove(column) } else { String::new() }
+			}));
+		}
+		let comparator: &dyn Fn(&str, &str) -> Ordering = match self.comparators.get(&column) {
+			Some(comparator) => comparator.as_ref(),
+			None => &compare::lexical,
+		};
+		let mut sorted: Vec<usize> = (0..cells.len()).collect();
+		sorted.sort_by(|a, b| {
+			let ordering = comparator(&cells[*a], &cells[*b]);
+			match order {
+				SortOrder::Ascending => ordering,
+				SortOrder::Descending => ordering.reverse(),
+			}
+		});
+		self.ranks = vec![0; sorted.len()];
+		for (rank, row) in sorted.into_iter().enumerate() {
+			self.ranks[row] = rank;
+		}
+	}
+
+	/// Fetches the lines displayed at a range of positions, with their
+	/// indexes. Consecutive lines are fetched at once.
+	fn fetch_positions(&mut self, positions: Range<usize>) -> Vec<(usize, Vec<String>)> {
+		let rows: Vec<usize> = positions.map(|position| self.row_at(position)).collect();
+		let mut lines = Vec::with_capacity(rows.len());
+		let mut start = 0;
+		while start < rows.len() {
+			let mut end = start + 1;
+			while end < rows.len() && rows[end] == rows[end - 1] + 1 {
+				end += 1;
+			}
+			let fetched = self.source.fetch(rows[start]..rows[end - 1] + 1);
+			lines.extend(rows[start..end].iter().copied().zip(fetched));
+			start = end;
+		}
+		lines
+	}
+
+	/// Fetches the lines of the current page.
+	fn load_page(&mut self) {
+		let start = self.page_start();
+		let end = start.saturating_add(self.items_by_page as usize).min(self.visible_row_count());
+		self.page = self.fetch_positions(start..end);
+		self.updated = true;
+	}
+
+	/// Returns the searched text, highlighted in the cells.
+	pub fn search(&self) -> &str {
+		&self.search
+	}
+
+	/// Searches a text, ignoring the case, and selects the first
+	/// displayed line containing it, from the selected line.
+	///
+	/// An empty text stops the search. Returns whether a line matches.
+	pub fn set_search(&mut self, search: &str) -> bool {
+		self.search = search.to_string();
+		self.updated = true;
+		let from = self.selected_position().unwrap_or(self.page_start());
+		self.select_match(from, true)
+	}
+
+	/// Selects the next displayed line containing the searched text,
+	/// starting again from the top after the last line.
+	///
+	/// Returns whether a line matches.
+	pub fn next_match(&mut self) -> bool {
+		let from = self.selected_position().map_or(0, |position| position + 1);
+		self.select_match(from, true)
+	}
+
+	/// Selects the previous displayed line containing the searched text,
+	/// starting again from the bottom before the first line.
+	///
+	/// Returns whether a line matches.
+	pub fn previous_match(&mut self) -> bool {
+		let from = self.selected_position()
+			.unwrap_or(0)
+			.checked_sub(1)
+			.unwrap_or(self.visible_row_count().saturating_sub(1));
+		self.select_match(from, false)
+	}
+
+	/// Selects the first displayed line containing the searched text,
+	/// looking from a position of the view in a direction and wrapping.
+	fn select_match(&mut self, from: usize, forward: bool) -> bool {
+		if self.search.is_empty() || self.visible_row_count() == 0 {
+			return false;
+		}
+		let count = self.visible_row_count();
+		let from = from % count;
+		let ranges = if forward { [from..count, 0..from] } else { [0..from + 1, from + 1..count] };
+		for range in ranges {
+			let mut chunks: Vec<Range<usize>> = range.clone()
+				.step_by(FETCH_CHUNK)
+				.map(|start| start..start.saturating_add(FETCH_CHUNK).min(range.end))
+				.collect();
+			if !forward {
+				chunks.reverse();
+			}
+			for chunk in chunks {
+				let mut lines = self.fetch_positions(chunk);
+				if !forward {
+					lines.reverse();
+				}
+				let found = lines.into_iter()
+					.find(|(_, cells)| cells.iter().any(|cell| !filter::find_text(cell, &self.search).is_empty()));
+				if let Some((row, _)) = found {
+					self.set_selected_row(Some(row));
+					return true;
+				}
+			}
+		}
+		false
+	}
+
+	/// Recomputes the displayed lines after the data, the sort or the
+	/// filter changed, scanning the whole source when there is a filter.
+	fn refresh_view(&mut self) {
+		self.view = None;
+		let row_count = self.source.row_count();
+		if let Some(filter) = &self.filter {
+			let mut view = Vec::new();
+			for start in (0..row_count).step_by(FETCH_CHUNK) {
+				let lines = self.source.fetch(start..start.saturating_add(FETCH_CHUNK).min(row_count));
+				view.extend(lines.iter()
+					.enumerate()
+					.filter(|(_, line)| filter.accepts(line))
+					.map(|(index, _)| start + index));
+			}
+			self.view = Some(view);
+		}
+		if !self.ranks.is_empty() {
+			let mut view = self.view.take().unwrap_or_else(|| (0..row_count).collect());
+			view.sort_by_key(|row| self.ranks[*row]);
+			self.view = Some(view);
+		}
+		self.selected_row = self.selected_row.filter(|row| self.position_of(*row).is_some());
+		self.current_page = self.current_page.min(self.page_count() - 1);
+		self.load_page();
+		if let Some(row) = self.selected_row {
+			self.set_selected_row(Some(row));
+		}
+	}
+
+	/// Returns the position of the selected line among the displayed lines.
+	fn selected_position(&self) -> Option<usize> {
+		self.position_of(self.selected_row?)
+	}
+
+	/// Returns the byte ranges to highlight in a cell: the matches of the
+	/// filter and of the search.
+	fn highlights(&self, cell: &str) -> Vec<(usize, usize)> {
+		let mut ranges = filter::find_text(cell, &self.search);
+		if let Some(filter) = &self.filter {
+			ranges.extend(filter.matches_in(cell));
+		}
+		ranges.sort();
+		ranges
+	}
+
+	/// Returns the headers as displayed, with the sort indicator.
+	fn header_labels(&self) -> Vec<String> {
+		self.columns.iter()
+			.enumerate()
+			.map(|(index, column)| match self.sorting {
+				Some((sorted, order)) if sorted == index => format!("{}{}", column.header, order.indicator()),
+				_ => column.header.clone(),
+			})
+			.collect()
+	}
+
+	/// Returns the cells of a line as displayed, formatted by their column.
+	fn display_line(&self, line: &[String]) -> Vec<String> {
+		line.iter()
+			.enumerate()
+			.map(|(index, cell)| match self.columns.get(index) {
+				Some(column) => column.display(cell),
+				None => cell.clone(),
+			})
+			.collect()
+	}
+
+	/// Scrolls the columns with Overflow::Scroll by a number of chars,
+	/// up to the end of their longest text on the current page.
+	///
+	/// Returns whether the columns scrolled.
+	fn scroll_by(&mut self, chars: isize) -> bool {
+		let longest = self.columns.iter()
+			.enumerate()
+			.filter(|(_, column)| column.overflow == Overflow::Scroll)
+			.flat_map(|(index, _)| self.page.iter().map(move |(_, line)| line.get(index)))
+			.map(|cell| cell.map_or(0, |cell| unicode::width(cell) as usize))
+			.max();
+		let Some(longest) = longest else {
+			return false;
+		};
+		let target = (self.horizontal_scroll as usize)
+			.saturating_add_signed(chars)
+			.min(longest) as u16;
+		if target == self.horizontal_scroll {
+			return false;
+		}
+		self.set_horizontal_scroll(target);
+		true
+	}
+
+	/// Returns the position, among the displayed lines, of the first
+	/// line of the current page.
+	fn page_start(&self) -> usize {
+		(self.current_page as usize)
+			.saturating_mul(self.items_by_page as usize)
+			.min(self.visible_row_count())
+	}
+
+	/// Moves the selected line by a number of lines, staying in the
+	/// displayed lines.
+	///
+	/// When no line is selected, the first line of the current page is.
+	/// Returns whether the selection changed.
+	fn move_selection(&mut self, lines: isize) -> bool {
+		let count = self.visible_row_count();
+		if count == 0 {
+			return false;
+		}
+		let last = count - 1;
+		let target = match self.selected_position() {
+			None => self.page_start().min(last),
+			Some(position) => position.saturating_add_signed(lines).min(last),
+		};
+		if Some(target) == self.selected_position() {
+			return false;
+		}
+		self.set_selected_row(Some(self.row_at(target)));
+		true
+	}
+
+	/// Handles the keys typed while the searched text is being typed.
+	///
+	/// Enter keeps the search, Escape stops it.
+	fn handle_search_key(&mut self, code: KeyCode) -> bool {
+		match code {
+			KeyCode::Char(c) => {
+				let mut search = self.search.clone();
+				search.push(c);
+				self.set_search(&search);
+			},
+			KeyCode::Backspace => {
+				let mut search = self.search.clone();
+				search.pop();
+				self.set_search(&search);
+			},
+			KeyCode::Enter => self.searching = false,
+			KeyCode::Esc => {
+				self.searching = false;
+				self.search.clear();
+			},
+			_ => return false,
+		}
+		self.updated = true;
+		true
+	}
+
+	/// Moves the selected column by a number of columns, when cells are selected.
+	///
+	/// Returns whether the selection changed.
+	fn move_column(&mut self, columns: isize) -> bool {
+		let Some(column) = self.selected_column else {
+			return false;
+		};
+		let target = column.saturating_add_signed(columns).min(self.columns.len().saturating_sub(1));
+		if target == column {
+			return false;
+		}
+		self.set_selected_column(Some(target));
+		true
+	}
+
+	/// Computes the width of each column, padding excluded.
+	///
+	/// The columns with ColumnWidth::Content are as wide as their widest
+	/// cell on the current page, the sized ones are sized against the width
+	/// left by the paddings and the borders, and the Size::Auto ones share
+	/// what the others left. All of them are kept between their minimum and
+	/// maximum widths.
+	fn column_widths(&self, width: u16) -> Vec<u16> {
+		let separators = if self.border.has_vertical() { self.columns.len().saturating_sub(1) } else { 0 };
+		let paddings = self.columns.len() * (self.cell_padding.left as usize + self.cell_padding.right as usize);
+		let available = (width as usize).saturating_sub(separators + paddings) as u16;
+		let labels = self.header_labels();
+		let mut widths: Vec<Option<u16>> = self.columns.iter()
+			.enumerate()
+			.map(|(index, column)| match &column.width {
+				ColumnWidth::Content => {
+					let content = self.page.iter()
+						.filter_map(|(_, line)| line.get(index))
+						.map(|cell| column.display(cell))
+						.chain(labels.get(index).cloned())
+						.map(|cell| unicode::width(&cell))
+						.max()
+						.unwrap_or(0);
+					Some(column.clamp(content))
+				},
+				ColumnWidth::Sized(size) => size.resolve(available).map(|width| column.clamp(width)),
+			})
+			.collect();
+		let used: u16 = widths.iter().flatten().fold(0, |used, width| used.saturating_add(*width));
+		let mut remaining = available.saturating_sub(used);
+		let mut autos = widths.iter().filter(|width| width.is_none()).count() as u16;
+		for (column, width) in self.columns.iter().zip(widths.iter_mut()) {
+			if width.is_none() {
+				let share = remaining.div_ceil(autos);
+				let share = column.clamp(share);
+				*width = Some(share);
+				remaining = remaining.saturating_sub(share);
+				autos -= 1;
+			}
+		}
+		widths.into_iter().map(|width| width.unwrap_or(0)).collect()
+	}
+
+	/// Returns the style of each cell of a line of data: the style of the
+	/// lines, then the ones given by the stylers, then the selection.
+	///
+	/// # Parameters
+	/// - row: the index of the line.
+	/// - line: the cells of the line.
+	/// - alternate: whether the line is one of every other line.
+	fn cell_styles(&self, row: usize, line: &[String], alternate: bool) -> Vec<Style> {
+		let mut style = self.style.patch(self.row_style);
+		if alternate {
+			style = style.patch(self.alt_row_style);
+		}
+		if let Some(row_style) = self.row_styler.as_ref().and_then(|styler| styler(row, line)) {
+			style = style.patch(row_style);
+		}
+		let selected = self.selected_row == Some(row);
+		(0..self.columns.len())
+			.map(|column| {
+				let mut style = style;
+				let cell_style = self.cell_styler.as_ref()
+					.and_then(|styler| styler(row, column, source::cell(line, column)));
+				if let Some(cell_style) = cell_style {
+					style = style.patch(cell_style);
+				}
+				match (selected, self.selected_column) {
+					(true, None) => style.patch(self.selected_style),
+					(true, Some(selected)) if selected == column => style.patch(self.selected_style),
+					(true, Some(_)) => style.bold(),
+					(false, _) => style,
+				}
+			})
+			.collect()
+	}
+
+	/// Draws a line of cells, padding included.
+	///
+	/// The line is as high as its highest cell, when texts are wrapped.
+	///
+	/// # Parameters
+	/// - cells: the cells of the line.
+	/// - widths: the width of each column.
+	/// - area: the area of the table left to draw on, from the first line
+	///   of the line of cells.
+	/// - styles: the style of each cell.
+	/// - highlighted: whether the matches of the filter and of the search
+	///   are highlighted, which they aren't in the headers.
+	/// - buffer: where the line is drawn.
+	///
+	/// Returns the line right after the drawn one.
+	fn render_line(&self, cells: &[String], widths: &[u16], area: Rect, styles: &[Style], highlighted: bool, buffer: &mut Buffer) -> u16 {
+		let padding = self.cell_padding;
+		let y = area.y;
+		let texts: Vec<Vec<(Range<usize>, bool)>> = self.columns.iter()
+			.zip(widths)
+			.enumerate()
+			.map(|(index, (column, width))| {
+				column.lines(cells.get(index).map_or("", String::as_str), *width, self.horizontal_scroll)
+			})
+			.collect();
+		let text_height = texts.iter().map(Vec::len).max().unwrap_or(1).max(1) as u16;
+		let height = text_height.saturating_add(padding.top).saturating_add(padding.bottom);
+		let text_y = y.saturating_add(padding.top);
+		let mut x = area.x;
+		for (index, width) in widths.iter().enumerate() {
+			if index > 0 && self.border.has_vertical() {
+				x = x.saturating_add(1);
+			}
+			let cell_width = width.saturating_add(padding.left).saturating_add(padding.right);
+			let style = styles.get(index).copied().unwrap_or(self.style);
+			if style != self.style {
+				let fill = Rect::new(x, y, cell_width, height).intersection(&area);
+				for line in fill.y..fill.bottom() {
+					for column in fill.x..fill.right() {
+						buffer.set(column, line, ' ', style);
+					}
+				}
+			}
+			let text_x = x.saturating_add(padding.left);
+			if let Some(cell) = cells.get(index) {
+				let highlights = if highlighted { self.highlights(cell) } else { Vec::new() };
+				for (line, (range, ellipsis)) in texts[index].iter().enumerate() {
+					let line_y = text_y.saturating_add(line as u16);
+					if line_y >= area.bottom() || text_x >= area.right() {
+						break;
+					}
+					let text = &cell[range.clone()];
+					let length = unicode::width(text) + *ellipsis as u16;
+					let line_x = text_x.saturating_add(self.columns[index].indent(length, *width));
+					if line_x >= area.right() {
+						continue;
+					}
+					let max_width = (*width).min(area.right() - line_x);
+					let ranges: Vec<(usize, usize)> = highlights.iter()
+						.filter(|(start, end)| *end > range.start && *start < range.end)
+						.map(|(start, end)| ((*start).max(range.start) - range.start, (*end).min(range.end) - range.start))
+						.collect();
+					let printed = print_highlighted(buffer, line_x, line_y, text, max_width, style, &ranges);
+					if *ellipsis && printed < max_width {
+						buffer.set(line_x + printed, line_y, '…', style);
+					}
+				}
+			}
+			x = x.saturating_add(cell_width);
+		}
+		y.saturating_add(height)
+	}
+
+	/// Draws the searched text on the last line of the table, while it's
+	/// being typed.
+	fn render_prompt(&self, area: Rect, buffer: &mut Buffer) {
+		if !self.searching || area.is_empty() {
+			return;
+		}
+		let y = area.bottom() - 1;
+		buffer.clear(Rect::new(area.x, y, area.width, 1));
+		let prompt = format!("/{}", self.search);
+		buffer.print(area.x, y, &prompt, area.width, self.style.bold());
+	}
+
+	/// Returns the columns of the buffer the vertical lines between the
+	/// columns are drawn on, when the border is vertical.
+	fn column_separators(&self, widths: &[u16], area: Rect) -> Vec<u16> {
+		if !self.border.has_vertical() {
+			return Vec::new();
+		}
+		let mut separators = Vec::new();
+		let mut x = area.x;
+		for width in &widths[..widths.len().saturating_sub(1)] {
+			x = x.saturating_add(*width + self.cell_padding.left + self.cell_padding.right);
+			if x >= area.right() {
+				break;
+			}
+			separators.push(x);
+			x = x.saturating_add(1);
+		}
+		separators
+	}
+
+	/// Draws the vertical lines between the columns, from the top to the
+	/// bottom of the table.
+	///
+	/// The lines are joined to the horizontal border around the table by
+	/// tees, where the border isn't covered by the title.
+	fn render_columns(&self, separators: &[u16], area: Rect, buffer: &mut Buffer) {
+		let border_style = self.style.patch(self.border_style);
+		for x in separators {
+			for y in area.y..area.bottom() {
+				buffer.set(*x, y, self.border.vertical, border_style);
+			}
+			if !self.border.has_horizontal() {
+				continue;
+			}
+			let horizontal = self.border.horizontal.to_string();
+			let joints = [
+				(area.y.checked_sub(1), self.border.top_tee),
+				(Some(area.bottom()), self.border.bottom_tee),
+			];
+			for (y, tee) in joints {
+				let Some(y) = y else {
+					continue;
+				};
+				if buffer.get(*x, y).is_some_and(|cell| cell.symbol == horizontal) {
+					buffer.set(*x, y, tee, border_style);
+				}
+			}
+		}
+	}
+
+	/// Draws the horizontal line between the headers and the data.
+	///
+	/// The line crosses the vertical lines between the columns, and is
+	/// joined to the vertical border around the table by tees, on the
+	/// sides where the border is drawn.
+	fn render_separator(&self, separators: &[u16], area: Rect, y: u16, buffer: &mut Buffer) {
+		let border_style = self.style.patch(self.border_style);
+		for x in area.x..area.right() {
+			buffer.set(x, y, self.border.horizontal, border_style);
+		}
+		if !self.border.has_vertical() {
+			return;
+		}
+		let vertical = self.border.vertical.to_string();
+		let joints = [
+			(area.x.checked_sub(1), self.border.left_tee),
+			(Some(area.right()), self.border.right_tee),
+		];
+		for (x, tee) in joints {
+			let Some(x) = x else {
+				continue;
+			};
+			if buffer.get(x, y).is_some_and(|cell| cell.symbol == vertical) {
+				buffer.set(x, y, tee, border_style);
+			}
+		}
+		for x in separators {
+			buffer.set(*x, y, self.border.cross, border_style);
+		}
+	}
+}
+
+/// Prints a text, with the given byte ranges in bold and underlined.
+///
+/// Returns the number of chars printed.
+fn print_highlighted(buffer: &mut Buffer, x: u16, y: u16, text: &str, max_width: u16, style: Style, ranges: &[(usize, usize)]) -> u16 {
+	let highlight = style.bold().underline();
+	let mut printed = 0;
+	let mut start = 0;
+	for (range_start, range_end) in ranges {
+		let range_start = (*range_start).max(start);
+		if *range_end <= range_start {
+			continue;
+		}
+		printed += buffer.print(x.saturating_add(printed), y, &text[start..range_start], max_width - printed, style);
+		printed += buffer.print(x.saturating_add(printed), y, &text[range_start..*range_end], max_width - printed, highlight);
+		start = *range_end;
+	}
+	printed + buffer.print(x.saturating_add(printed), y, &text[start..], max_width - printed, style)
+}
+
 impl UIElement for Table {
 	/// Returns the z index.
 	fn z_index(&self) -> u8 {
@@ -123,6 +1234,7 @@ impl UIElement for Table {
 	/// Sets the z index.
 	fn set_z_index(&mut self, z_index: u8) {
 		self.z_index = z_index;
+		self.updated = true;
 	}
 	
 	/// Returns the title of the table.
@@ -133,6 +1245,62 @@ impl UIElement for Table {
 	/// Sets the title.
 	fn set_title(&mut self, title: &str) {
 		self.title = title.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the title is placed on the top border.
+	fn title_align(&self) -> Align {
+		self.title_align
+	}
+
+	/// Sets where the title is placed on the top border.
+	fn set_title_align(&mut self, align: Align) {
+		self.title_align = align;
+		self.updated = true;
+	}
+
+	/// Returns the footer of the table.
+	fn footer(&self) -> &str {
+		self.footer.as_str()
+	}
+
+	/// Sets the footer.
+	fn set_footer(&mut self, footer: &str) {
+		self.footer = footer.to_string();
+		self.updated = true;
+	}
+
+	/// Returns where the footer is placed on the bottom border.
+	fn footer_align(&self) -> Align {
+		self.footer_align
+	}
+
+	/// Sets where the footer is placed on the bottom border.
+	fn set_footer_align(&mut self, align: Align) {
+		self.footer_align = align;
+		self.updated = true;
+	}
+
+	/// Returns how the table is positioned.
+	fn position(&self) -> Position {
+		self.position
+	}
+
+	/// Sets how the table is positioned.
+	fn set_position(&mut self, position: Position) {
+		self.position = position;
+		self.updated = true;
+	}
+
+	/// Returns the offset of the table.
+	fn offset(&self) -> (u16, u16) {
+		self.offset
+	}
+
+	/// Sets the offset of the table.
+	fn set_offset(&mut self, x: u16, y: u16) {
+		self.offset = (x, y);
+		self.updated = true;
 	}
 
 	/// Returns the width of the table.
@@ -143,6 +1311,7 @@ impl UIElement for Table {
 	/// Sets the width.
 	fn set_width(&mut self, width: Size) {
 		self.width = width.clone();
+		self.updated = true;
 	}
 
 	/// Returns the height of the table.
@@ -153,6 +1322,7 @@ impl UIElement for Table {
 	/// Sets the height.
 	fn set_height(&mut self, height: Size) {
 		self.height = height.clone();
+		self.updated = true;
 	}
 
 	/// Returns whether the table has been updated and
@@ -166,24 +1336,639 @@ impl UIElement for Table {
 		self.updated = updated;
 	}
 
-	/// Returns the vertical border character.
-	fn border_vertical(&self) -> char {
-		self.border_vertical
+	/// Returns true, a table can be focused.
+	fn focusable(&self) -> bool {
+		true
+	}
+
+	/// Returns whether the table is focused.
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	/// Changes the focus state of the table.
+	fn set_focused(&mut self, focused: bool) {
+		if focused != self.focused {
+			self.focused = focused;
+			self.updated = true;
+		}
+	}
+
+	/// Returns the chars the border is drawn with.
+	fn border(&self) -> BorderSet {
+		self.border
+	}
+
+	/// Sets the chars the border, and the lines between the columns and
+	/// below the headers, are drawn with.
+	fn set_border(&mut self, border: BorderSet) {
+		self.border = border;
+		self.updated = true;
+	}
+
+	/// Returns the sides the border is drawn on.
+	fn border_sides(&self) -> Sides<bool> {
+		self.border_sides
+	}
+
+	/// Sets the sides the border is drawn on. The lines between the
+	/// columns and below the headers are drawn whatever the sides.
+	fn set_border_sides(&mut self, sides: Sides<bool>) {
+		self.border_sides = sides;
+		self.updated = true;
+	}
+
+	/// Returns the space between the border and the table.
+	fn padding(&self) -> Sides<u16> {
+		self.padding
+	}
+
+	/// Sets the space between the border and the table.
+	///
+	/// See `set_cell_padding` for the space around the text of the cells.
+	fn set_padding(&mut self, padding: Sides<u16>) {
+		self.padding = padding;
+		self.updated = true;
+	}
+
+	/// Returns the space around the border.
+	fn margin(&self) -> Sides<u16> {
+		self.margin
+	}
+
+	/// Sets the space around the border.
+	fn set_margin(&mut self, margin: Sides<u16>) {
+		self.margin = margin;
+		self.updated = true;
+	}
+
+	/// Returns the style of the table.
+	fn style(&self) -> Style {
+		self.style
+	}
+
+	/// Sets the style of the table.
+	fn set_style(&mut self, style: Style) {
+		self.style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the border.
+	fn border_style(&self) -> Style {
+		self.border_style
+	}
+
+	/// Sets the style of the border.
+	fn set_border_style(&mut self, style: Style) {
+		self.border_style = style;
+		self.updated = true;
+	}
+
+	/// Returns the style of the title.
+	fn title_style(&self) -> Style {
+		self.title_style
+	}
+
+	/// Sets the style of the title.
+	fn set_title_style(&mut self, style: Style) {
+		self.title_style = style;
+		self.updated = true;
 	}
-	/// Returns the horizontal border character.
-	fn border_horizontal(&self) -> char {
-		self.border_horizontal
+
+	/// Draws the headers and the lines of the current page.
+	///
+	/// The vertical border separates the columns, the horizontal
+	/// border separates the headers from the data, with tees and crosses
+	/// where the lines meet. The header of the sorted column shows the
+	/// order. The headers, the lines and the cells are drawn in their
+	/// style, every other line in the alternate style. The selected line,
+	/// or the selected cell, is drawn in the selected style. The matches of
+	/// the filter and of the search are highlighted, and the searched text
+	/// is displayed while it's typed.
+	fn render(&self, area: Rect, buffer: &mut Buffer) {
+		let widths = self.column_widths(area.width);
+		let separators = self.column_separators(&widths, area);
+		self.render_columns(&separators, area, buffer);
+		let header_styles = vec![self.style.patch(self.header_style); self.columns.len()];
+		let mut y = self.render_line(&self.header_labels(), &widths, area, &header_styles, false, buffer);
+		if self.border.has_horizontal() && y < area.bottom() {
+			self.render_separator(&separators, area, y, buffer);
+			y += 1;
+		}
+		for (position, (row, line)) in self.page.iter().enumerate() {
+			if y >= area.bottom() {
+				break;
+			}
+			let styles = self.cell_styles(*row, line, !position.is_multiple_of(2));
+			let rest = Rect::new(area.x, y, area.width, area.bottom() - y);
+			y = self.render_line(&self.display_line(line), &widths, rest, &styles, true, buffer);
+		}
+		self.render_prompt(area, buffer);
+	}
+
+	/// Applies the roles of a theme for the tables, e.g. table.border, and
+	/// the roles of the table: table.header, table.row, table.row.alt and
+	/// table.selected.
+	fn apply_theme(&mut self, theme: &Theme) {
+		theme.apply_element(self, "table");
+		if let Some(style) = theme.style("table.header") {
+			self.header_style = style;
+		}
+		if let Some(style) = theme.style("table.row") {
+			self.row_style = style;
+		}
+		if let Some(style) = theme.style("table.row.alt") {
+			self.alt_row_style = style;
+		}
+		if let Some(style) = theme.style("table.selected") {
+			self.selected_style = style;
+		}
+		self.updated = true;
+	}
+
+	/// Moves the selection with the arrows, Page Up, Page Down, Home and
+	/// End, and calls the activation function on Enter. Shift+Left and
+	/// Shift+Right scroll the columns with Overflow::Scroll.
+	///
+	/// `/` starts typing a text to search, `n` and `N` select the next
+	/// and previous lines containing it.
+	///
+	/// Keys are only handled when the table is focused. When the selection
+	/// can't move further, the key is not handled, so that the focus can
+	/// move to the next element.
+	fn handle_event(&mut self, event: &Event) -> bool {
+		let Event::Key(key) = event else {
+			return false;
+		};
+		if !self.focused {
+			return false;
+		}
+		let shift_only = key.modifiers.shift && !key.modifiers.alt && !key.modifiers.control;
+		match (key.code, shift_only) {
+			(KeyCode::Left, true) if !self.searching => return self.scroll_by(-1),
+			(KeyCode::Right, true) if !self.searching => return self.scroll_by(1),
+			_ => {},
+		}
+		if !key.modifiers.is_empty() {
+			return false;
+		}
+		if self.searching {
+			return self.handle_search_key(key.code);
+		}
+		let page = self.items_by_page as isize;
+		match key.code {
+			KeyCode::Char('/') => {
+				self.searching = true;
+				self.search.clear();
+				self.updated = true;
+				true
+			},
+			KeyCode::Char('n') => self.next_match(),
+			KeyCode::Char('N') => self.previous_match(),
+			KeyCode::Up => self.move_selection(-1),
+			KeyCode::Down => self.move_selection(1),
+			KeyCode::PageUp => self.move_selection(-page),
+			KeyCode::PageDown => self.move_selection(page),
+			KeyCode::Home => self.move_selection(isize::MIN),
+			KeyCode::End => self.move_selection(isize::MAX),
+			KeyCode::Left => self.move_column(-1),
+			KeyCode::Right => self.move_column(1),
+			KeyCode::Enter => {
+				let selected = self.selected_row
+					.and_then(|row| self.page.iter().find(|(index, _)| *index == row));
+				match (selected, self.on_activate.as_mut()) {
+					(Some((row, line)), Some(on_activate)) => {
+						on_activate(*row, line);
+						true
+					},
+					_ => false,
+				}
+			},
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+	use std::rc::Rc;
+
+	use super::*;
+	use crate::event::{KeyEvent, Modifiers};
+	use pattern::Pattern;
+
+	/// Returns a table of hosts, with their name and their load.
+	fn hosts() -> Table {
+		let rows = [("cedar", "10"), ("ash", "9"), ("birch", "10"), ("alder", "200")]
+			.iter()
+			.map(|(name, load)| vec![name.to_string(), load.to_string()])
+			.collect();
+		Table::new(&["Host".to_string(), "Load".to_string()], rows)
+	}
+
+	/// Returns the first cell of each line of the current page.
+	fn displayed(table: &Table) -> Vec<&str> {
+		table.page.iter().map(|(_, line)| line[0].as_str()).collect()
 	}
-	/// Returns the intersection border character.
-	fn border_intersect(&self) -> char {
-		self.border_intersect
+
+	/// A source of numbered lines, logging the ranges fetched.
+	struct Counting {
+		/// The number of lines.
+		rows: usize,
+		/// The start and the end of the ranges fetched so far.
+		fetched: Rc<RefCell<Vec<(usize, usize)>>>,
 	}
-	/// Returns the vertical padding.
-	fn padding_vertical(&self) -> u8 {
-		self.padding_vertical
+
+	impl TableSource for Counting {
+		fn headers(&self) -> Vec<String> {
+			vec!["Line".to_string()]
+		}
+
+		fn row_count(&self) -> usize {
+			self.rows
+		}
+
+		fn fetch(&mut self, rows: Range<usize>) -> Vec<Vec<String>> {
+			self.fetched.borrow_mut().push((rows.start, rows.end));
+			rows.map(|row| vec![row.to_string()]).collect()
+		}
+	}
+
+	/// Returns a table of a single line, with the given columns.
+	fn with_columns(columns: Vec<Column>, line: &[&str]) -> Table {
+		let headers: Vec<String> = columns.iter().map(|column| column.header.clone()).collect();
+		let mut table = Table::new(&headers, vec![line.iter().map(|cell| cell.to_string()).collect()]);
+		table.set_columns(columns);
+		table
 	}
-	/// Returns the horizontal padding.
-	fn padding_horizontal(&self) -> u8 {
-		self.padding_horizontal
+
+	/// Presses a key without modifiers, returning whether it was handled.
+	fn press(table: &mut Table, code: KeyCode) -> bool {
+		table.handle_event(&Event::Key(KeyEvent::new(code, Modifiers::default())))
+	}
+
+	#[test]
+	fn keys_move_the_selection_across_pages() {
+		let mut table = hosts();
+		table.set_items_by_page(2).unwrap();
+		assert!(!press(&mut table, KeyCode::Down));
+		table.set_focused(true);
+
+		let moves = [
+			(KeyCode::Down, 0, 0),
+			(KeyCode::Down, 1, 0),
+			(KeyCode::Down, 2, 1),
+			(KeyCode::Up, 1, 0),
+			(KeyCode::End, 3, 1),
+			(KeyCode::PageUp, 1, 0),
+			(KeyCode::Home, 0, 0),
+			(KeyCode::PageDown, 2, 1),
+		];
+		for (code, row, page) in moves {
+			assert!(press(&mut table, code), "{:?}", code);
+			assert_eq!((table.selected_row(), table.current_page()), (Some(row), page), "{:?}", code);
+		}
+		assert_eq!(displayed(&table), ["birch", "alder"]);
+		assert!(press(&mut table, KeyCode::PageDown));
+		assert_eq!(table.selected_row(), Some(3));
+		assert!(!press(&mut table, KeyCode::Down));
+		assert!(!press(&mut table, KeyCode::End));
+		assert!(press(&mut table, KeyCode::Home));
+		assert!(!press(&mut table, KeyCode::Up));
+		assert!(!press(&mut table, KeyCode::PageUp));
+	}
+
+	#[test]
+	fn selection_of_rows_and_cells() {
+		let mut table = hosts();
+		table.set_items_by_page(2).unwrap();
+		table.set_selected_row(Some(99));
+		assert_eq!((table.selected_row(), table.current_page()), (Some(3), 1));
+		table.set_selected_row(None);
+		assert_eq!(table.selected_row(), None);
+
+		table.set_focused(true);
+		assert!(!press(&mut table, KeyCode::Right));
+		table.set_selected_column(Some(9));
+		assert_eq!(table.selected_column(), Some(1));
+		assert!(!press(&mut table, KeyCode::Right));
+		assert!(press(&mut table, KeyCode::Left));
+		assert_eq!(table.selected_column(), Some(0));
+		assert!(!press(&mut table, KeyCode::Left));
+		table.set_selected_column(None);
+		assert!(!press(&mut table, KeyCode::Left));
+	}
+
+	#[test]
+	fn enter_activates_the_selected_line() {
+		let activated = Rc::new(RefCell::new(Vec::new()));
+		let mut table = hosts();
+		let log = Rc::clone(&activated);
+		table.set_on_activate(move |row, line| log.borrow_mut().push((row, line[0].clone())));
+		table.set_focused(true);
+		assert!(!press(&mut table, KeyCode::Enter));
+
+		table.set_selected_row(Some(2));
+		assert!(press(&mut table, KeyCode::Enter));
+		table.set_selected_row(Some(0));
+		assert!(press(&mut table, KeyCode::Enter));
+		assert_eq!(*activated.borrow(), [(2, "birch".to_string()), (0, "cedar".to_string())]);
+
+		let mut table = hosts();
+		table.set_focused(true);
+		table.set_selected_row(Some(0));
+		assert!(!press(&mut table, KeyCode::Enter));
+	}
+
+	#[test]
+	fn sorting_keeps_the_source_order() {
+		let mut table = hosts();
+		table.sort_by(0, SortOrder::Ascending);
+		assert_eq!(displayed(&table), ["alder", "ash", "birch", "cedar"]);
+		assert_eq!(table.header_labels()[0], "Host ▲");
+		let source: Vec<String> = table.source_mut().fetch(0..4).into_iter().map(|line| line[0].clone()).collect();
+		assert_eq!(source, ["cedar", "ash", "birch", "alder"]);
+
+		table.clear_sorting();
+		assert_eq!(table.sorting(), None);
+		assert_eq!(displayed(&table), ["cedar", "ash", "birch", "alder"]);
+		assert_eq!(table.header_labels()[0], "Host");
+	}
+
+	#[test]
+	fn sorting_is_stable() {
+		let mut table = hosts();
+		table.set_comparator(1, compare::numeric);
+		table.sort_by(1, SortOrder::Ascending);
+		assert_eq!(displayed(&table), ["ash", "cedar", "birch", "alder"]);
+		table.sort_by(1, SortOrder::Descending);
+		assert_eq!(displayed(&table), ["alder", "cedar", "birch", "ash"]);
+	}
+
+	#[test]
+	fn comparator_change_sorts_again() {
+		let mut table = hosts();
+		table.sort_by(1, SortOrder::Ascending);
+		assert_eq!(displayed(&table), ["cedar", "birch", "alder", "ash"]);
+		table.set_comparator(1, compare::numeric);
+		assert_eq!(displayed(&table), ["ash", "cedar", "birch", "alder"]);
+	}
+
+	#[test]
+	fn sorting_cycles_through_no_sort() {
+		let mut table = hosts();
+		table.cycle_sorting(0);
+		assert_eq!(table.sorting(), Some((0, SortOrder::Ascending)));
+		table.cycle_sorting(0);
+		assert_eq!(table.sorting(), Some((0, SortOrder::Descending)));
+		assert_eq!(displayed(&table), ["cedar", "birch", "ash", "alder"]);
+		table.cycle_sorting(0);
+		assert_eq!(table.sorting(), None);
+		assert_eq!(displayed(&table), ["cedar", "ash", "birch", "alder"]);
+		table.cycle_sorting(0);
+		table.cycle_sorting(1);
+		assert_eq!(table.sorting(), Some((1, SortOrder::Ascending)));
+	}
+
+	#[test]
+	fn sorting_keeps_the_selection_and_the_filter() {
+		let mut table = hosts();
+		table.set_items_by_page(2).unwrap();
+		table.set_selected_row(Some(0));
+		table.sort_by(0, SortOrder::Ascending);
+		assert_eq!(table.selected_row(), Some(0));
+		assert_eq!(table.current_page(), 1);
+		assert_eq!(displayed(&table), ["birch", "cedar"]);
+
+		table.set_filter(Some(Filter::Contains("a".to_string())));
+		assert_eq!(table.visible_row_count(), 3);
+		assert_eq!(table.current_page(), 1);
+		assert_eq!(displayed(&table), ["cedar"]);
+		assert!(table.move_selection(-1));
+		assert_eq!(table.selected_row(), Some(1));
+
+		table.clear_sorting();
+		assert_eq!(table.selected_row(), Some(1));
+		assert_eq!(table.current_page(), 0);
+		assert_eq!(displayed(&table), ["cedar", "ash"]);
+	}
+
+	#[test]
+	fn filtered_out_lines_are_not_selected() {
+		let mut table = hosts();
+		table.set_items_by_page(1).unwrap();
+		table.set_selected_row(Some(3));
+		assert_eq!(table.current_page(), 3);
+
+		table.set_filter(Some(Filter::Contains("c".to_string())));
+		assert_eq!(table.selected_row(), None);
+		assert_eq!(table.page_count(), 2);
+		assert_eq!(table.current_page(), 1);
+		table.set_selected_row(Some(1));
+		assert_eq!(table.selected_row(), None);
+
+		table.set_focused(true);
+		table.set_current_page(0).unwrap();
+		assert!(press(&mut table, KeyCode::Down));
+		assert_eq!(table.selected_row(), Some(0));
+		assert!(press(&mut table, KeyCode::Down));
+		assert_eq!((table.selected_row(), table.current_page()), (Some(2), 1));
+		assert_eq!(displayed(&table), ["birch"]);
+		assert!(!press(&mut table, KeyCode::Down));
+	}
+
+	#[test]
+	fn filters_drop_lines_and_pages() {
+		let mut table = hosts();
+		table.set_items_by_page(2).unwrap();
+		table.set_current_page(1).unwrap();
+		assert_eq!(table.page_count(), 2);
+
+		table.set_filter(Some(Filter::Matches(Pattern::new("^a").unwrap())));
+		assert_eq!((table.page_count(), table.current_page()), (1, 0));
+		assert_eq!(displayed(&table), ["ash", "alder"]);
+
+		table.set_filter(Some(Filter::Column(1, Box::new(|load| load == "10"))));
+		assert_eq!(displayed(&table), ["cedar", "birch"]);
+
+		table.set_filter(Some(Filter::Contains("nothing".to_string())));
+		assert_eq!((table.visible_row_count(), table.page_count()), (0, 1));
+		assert!(displayed(&table).is_empty());
+
+		table.set_filter(None);
+		assert_eq!(table.page_count(), 2);
+		assert_eq!(displayed(&table), ["cedar", "ash"]);
+
+		assert!(Pattern::new("a(b").is_err());
+		assert!(Pattern::new("[z-a]").is_err());
+		assert!(Pattern::new("*a").is_err());
+	}
+
+	#[test]
+	fn search_wraps_around() {
+		let mut table = hosts();
+		table.set_items_by_page(2).unwrap();
+		table.set_focused(true);
+		for code in [KeyCode::Char('/'), KeyCode::Char('A'), KeyCode::Enter] {
+			assert!(press(&mut table, code));
+		}
+		assert_eq!(table.search(), "A");
+		assert_eq!(table.selected_row(), Some(0));
+
+		let moves = [
+			(KeyCode::Char('n'), 1, 0),
+			(KeyCode::Char('n'), 3, 1),
+			(KeyCode::Char('n'), 0, 0),
+			(KeyCode::Char('N'), 3, 1),
+			(KeyCode::Char('N'), 1, 0),
+		];
+		for (code, row, page) in moves {
+			assert!(press(&mut table, code));
+			assert_eq!((table.selected_row(), table.current_page()), (Some(row), page));
+		}
+
+		assert!(!table.set_search("nothing"));
+		assert_eq!(table.selected_row(), Some(1));
+		assert!(!press(&mut table, KeyCode::Char('n')));
+	}
+
+	#[test]
+	fn headers_are_not_highlighted() {
+		let mut table = hosts();
+		table.set_search("D");
+		let mut buffer = Buffer::new(20, 8);
+		table.render(buffer.area(), &mut buffer);
+		let lines = buffer.lines();
+		let header = lines.iter().position(|line| line.contains("Host")).unwrap() as u16;
+		let cedar = lines.iter().position(|line| line.contains("cedar")).unwrap() as u16;
+		let styles = |y: u16| (0..buffer.width())
+			.filter_map(|x| buffer.get(x, y))
+			.filter(|cell| cell.symbol == "d")
+			.map(|cell| cell.style.underline)
+			.collect::<Vec<bool>>();
+		assert_eq!(styles(header), [false]);
+		assert_eq!(styles(cedar), [true]);
+	}
+
+	#[test]
+	fn only_the_displayed_page_is_fetched() {
+		let fetched = Rc::new(RefCell::new(Vec::new()));
+		let mut table = Table::with_source(Box::new(Counting { rows: 1_000_000, fetched: Rc::clone(&fetched) }));
+		assert_eq!(*fetched.borrow(), [(0, 20)]);
+		table.set_items_by_page(10).unwrap();
+		assert_eq!(table.page_count(), 100_000);
+
+		fetched.borrow_mut().clear();
+		table.set_current_page(500).unwrap();
+		assert_eq!(*fetched.borrow(), [(5000, 5010)]);
+		assert_eq!(displayed(&table)[0], "5000");
+
+		fetched.borrow_mut().clear();
+		table.set_focused(true);
+		table.set_selected_row(Some(5009));
+		assert!(press(&mut table, KeyCode::Up));
+		assert!(fetched.borrow().is_empty());
+		assert!(press(&mut table, KeyCode::PageDown));
+		assert!(press(&mut table, KeyCode::End));
+		assert_eq!(*fetched.borrow(), [(5010, 5020), (999_990, 1_000_000)]);
+		assert_eq!(table.current_page(), 99_999);
+	}
+
+	#[test]
+	fn ragged_rows_are_rejected() {
+		let headers = ["Host".to_string(), "Load".to_string()];
+		let rows = vec![vec!["cedar".to_string(), "10".to_string()], vec!["ash".to_string()]];
+		let error = Table::try_new(&headers, rows).err().unwrap();
+		assert!(matches!(error, Error::RaggedRow { row: 1, expected: 2, found: 1 }));
+		assert_eq!(error.to_string(), "line 1 has 1 cells, expected 2");
+		assert!(Table::try_new(&headers, vec![vec!["cedar".to_string(), "10".to_string()]]).is_ok());
+	}
+
+	#[test]
+	fn pages_out_of_range_are_rejected() {
+		let mut table = hosts();
+		table.set_items_by_page(3).unwrap();
+		table.set_current_page(1).unwrap();
+		let error = table.set_current_page(2).unwrap_err();
+		assert!(matches!(error, Error::PageOutOfRange { page: 2, page_count: 2 }));
+		assert_eq!(error.to_string(), "page 2 is out of range, there are 2 pages");
+		assert_eq!(table.current_page(), 1);
+	}
+
+	#[test]
+	fn zero_items_by_page_is_rejected() {
+		let mut table = hosts();
+		table.set_items_by_page(3).unwrap();
+		assert!(matches!(table.set_items_by_page(0), Err(Error::ZeroItemsByPage)));
+		assert_eq!(table.items_by_page(), 3);
+		assert_eq!(displayed(&table), ["cedar", "ash", "birch"]);
+	}
+
+	#[test]
+	fn column_widths_are_distributed() {
+		let table = with_columns(vec![
+			Column::new("A").width(ColumnWidth::Sized(Size::Chars(6))),
+			Column::new("B").width(ColumnWidth::Sized(Size::Percents(25))),
+			Column::new("C").width(ColumnWidth::Content),
+			Column::new("D").width(ColumnWidth::Sized(Size::Auto)),
+		], &["x", "y", "content", "z"]);
+		// 48 chars, less a padding of 1 on both sides of the 4 columns.
+		assert_eq!(table.column_widths(48), [6, 10, 7, 17]);
+		assert_eq!(table.column_widths(20), [6, 3, 7, 0]);
+
+		let mut table = with_columns(vec![
+			Column::new("Header").width(ColumnWidth::Content),
+			Column::new("B").width(ColumnWidth::Content).max_width(3),
+			Column::new("C").width(ColumnWidth::Sized(Size::Chars(1))).min_width(4),
+		], &["x", "content", "y"]);
+		table.set_cell_padding(Sides::all(0));
+		table.set_border(BorderSet::SINGLE);
+		assert_eq!(table.column_widths(30), [6, 3, 4]);
+	}
+
+	#[test]
+	fn column_widths_share_the_leftovers() {
+		let mut table = with_columns(vec![
+			Column::new("A").width(ColumnWidth::Sized(Size::Percents(33))),
+			Column::new("B").width(ColumnWidth::Sized(Size::Percents(33))),
+			Column::new("C").width(ColumnWidth::Sized(Size::Auto)),
+		], &["", "", ""]);
+		table.set_cell_padding(Sides::all(0));
+		assert_eq!(table.column_widths(10), [3, 3, 4]);
+
+		let mut table = with_columns(vec![
+			Column::new("A").width(ColumnWidth::Sized(Size::Chars(3))),
+			Column::new("B").width(ColumnWidth::Sized(Size::Auto)),
+			Column::new("C").width(ColumnWidth::Sized(Size::Auto)),
+		], &["", "", ""]);
+		table.set_cell_padding(Sides::all(0));
+		assert_eq!(table.column_widths(10), [3, 4, 3]);
+
+		let mut table = with_columns(vec![
+			Column::new("A").width(ColumnWidth::Sized(Size::Auto)).max_width(2),
+			Column::new("B").width(ColumnWidth::Sized(Size::Auto)),
+		], &["", ""]);
+		table.set_cell_padding(Sides::all(0));
+		assert_eq!(table.column_widths(10), [2, 8]);
+	}
+
+	#[test]
+	fn overflowing_cells_are_cut() {
+		let mut table = with_columns(vec![
+			Column::new("Ellipsis").width(ColumnWidth::Sized(Size::Chars(5))).overflow(Overflow::Ellipsis),
+			Column::new("Scroll").width(ColumnWidth::Sized(Size::Chars(5))).overflow(Overflow::Scroll),
+		], &["birches", "cedars"]);
+		table.set_cell_padding(Sides::all(0));
+		table.set_border(BorderSet::SINGLE);
+		let mut buffer = Buffer::new(11, 3);
+		table.render(buffer.area(), &mut buffer);
+		assert_eq!(buffer.lines(), ["Elli…│Scrol", "─────┼─────", "birc…│cedar"]);
+
+		// The scroll stops at the end of the text.
+		table.set_horizontal_scroll(2);
+		let mut buffer = Buffer::new(11, 3);
+		table.render(buffer.area(), &mut buffer);
+		assert_eq!(buffer.lines()[2], "birc…│edars");
 	}
 }
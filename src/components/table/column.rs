@@ -0,0 +1,223 @@
+//! This module contains the description of the columns of a Table.
+
+use std::ops::Range;
+
+use super::format::Format;
+use crate::components::Size;
+use crate::unicode;
+
+pub use crate::layout::Align;
+
+/// How the width of a column is computed.
+#[derive(Clone)]
+pub enum ColumnWidth {
+	/// As wide as the widest cell of the current page, header included.
+	Content,
+	/// Sized as the UI elements: Size::Chars is a fixed width,
+	/// Size::Percents a part of the width of the table, and Size::Auto
+	/// shares what the other columns left with the other Auto columns.
+	Sized(Size),
+}
+
+/// What is done with the texts wider than their column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+	/// The text is cut, and ends with an ellipsis.
+	Ellipsis,
+	/// The text continues on the next lines, the line being as high as
+	/// its highest cell.
+	Wrap,
+	/// The text is cut, and scrolls horizontally with the table.
+	Scroll,
+}
+
+/// Describes a column of a Table.
+#[derive(Clone)]
+pub struct Column {
+	/// The header of the column.
+	pub header: String,
+	/// How the width of the column is computed.
+	pub width: ColumnWidth,
+	/// The minimum width of the column, padding excluded.
+	pub min_width: Option<u16>,
+	/// The maximum width of the column, padding excluded.
+	pub max_width: Option<u16>,
+	/// What is done with the texts wider than the column.
+	pub overflow: Overflow,
+	/// Where the texts are placed in the column, header included.
+	pub align: Align,
+	/// How the cells are displayed, if they are formatted.
+	pub format: Option<Format>,
+}
+
+impl Column {
+	/// Creates a new Column, as wide as its content, with an ellipsis
+	/// ending the texts too wide, and the texts on the left.
+	pub fn new(header: &str) -> Self {
+		Self {
+			header: header.to_string(),
+			width: ColumnWidth::Content,
+			min_width: None,
+			max_width: None,
+			overflow: Overflow::Ellipsis,
+			align: Align::Left,
+			format: None,
+		}
+	}
+
+	/// Returns the column with the given width.
+	pub fn width(mut self, width: ColumnWidth) -> Self {
+		self.width = width;
+		self
+	}
+
+	/// Returns the column with the given minimum width.
+	pub fn min_width(mut self, min_width: u16) -> Self {
+		self.min_width = Some(min_width);
+		self
+	}
+
+	/// Returns the column with the given maximum width.
+	pub fn max_width(mut self, max_width: u16) -> Self {
+		self.max_width = Some(max_width);
+		self
+	}
+
+	/// Returns the column with the given overflow.
+	pub fn overflow(mut self, overflow: Overflow) -> Self {
+		self.overflow = overflow;
+		self
+	}
+
+	/// Returns the column with the given alignment.
+	pub fn align(mut self, align: Align) -> Self {
+		self.align = align;
+		self
+	}
+
+	/// Returns the column with the given format.
+	pub fn format(mut self, format: Format) -> Self {
+		self.format = Some(format);
+		self
+	}
+
+	/// Returns a cell as displayed, formatted if the column has a format.
+	pub fn display(&self, cell: &str) -> String {
+		match &self.format {
+			Some(format) => format.apply(cell),
+			None => cell.to_string(),
+		}
+	}
+
+	/// Returns the space left before a text of a given length, to align it.
+	pub(super) fn indent(&self, length: u16, width: u16) -> u16 {
+		self.align.indent(length, width)
+	}
+
+	/// Applies the minimum and the maximum widths to a width.
+	pub(super) fn clamp(&self, width: u16) -> u16 {
+		let width = self.max_width.map_or(width, |max| width.min(max));
+		self.min_width.map_or(width, |min| width.max(min))
+	}
+
+	/// Splits a cell into the lines displayed in the column, without
+	/// splitting the graphemes.
+	///
+	/// Each line is a byte range of the text, and whether it ends with an
+	/// ellipsis.
+	///
+	/// # Parameters
+	/// - text: the text of the cell.
+	/// - width: the width of the column, in cells.
+	/// - scroll: the number of cells scrolled, for Overflow::Scroll.
+	pub(super) fn lines(&self, text: &str, width: u16, scroll: u16) -> Vec<(Range<usize>, bool)> {
+		let length = unicode::width(text);
+		if length <= width {
+			return vec![(0..text.len(), false)];
+		}
+		match self.overflow {
+			Overflow::Ellipsis => {
+				let (kept, _) = unicode::truncate(text, width.saturating_sub(1));
+				vec![(0..kept.len(), width > 0)]
+			},
+			Overflow::Scroll => {
+				let scroll = scroll.min(length - width);
+				let mut skipped = 0;
+				let start = unicode::graphemes(text)
+					.find(|(_, grapheme)| {
+						let found = skipped >= scroll;
+						skipped += unicode::grapheme_width(grapheme);
+						found
+					})
+					.map_or(text.len(), |(offset, _)| offset);
+				let (kept, kept_width) = unicode::truncate(&text[start..], width);
+				// A wide grapheme cut by the edge is replaced by an ellipsis.
+				let cut = kept_width < width && start + kept.len() < text.len();
+				vec![(start..start + kept.len(), cut)]
+			},
+			Overflow::Wrap => wrap(text, width),
+		}
+	}
+}
+
+/// Splits a text into lines of a maximum width, at the spaces when
+/// possible. The spaces ending the lines are dropped, and the graphemes
+/// wider than the column are displayed as an ellipsis.
+fn wrap(text: &str, width: u16) -> Vec<(Range<usize>, bool)> {
+	if width == 0 {
+		return vec![(0..0, false)];
+	}
+	let graphemes: Vec<(usize, &str)> = unicode::graphemes(text).collect();
+	let offset = |index: usize| graphemes.get(index).map_or(text.len(), |(offset, _)| *offset);
+	let mut lines = Vec::new();
+	let mut start = 0;
+	while start < graphemes.len() {
+		let mut end = start;
+		let mut used = 0;
+		while end < graphemes.len() {
+			let grapheme_width = unicode::grapheme_width(graphemes[end].1);
+			if used + grapheme_width > width && end > start {
+				break;
+			}
+			used += grapheme_width;
+			end += 1;
+		}
+		if end < graphemes.len() {
+			if let Some(space) = (start + 1..=end).rev().find(|index| graphemes[*index].1 == " ") {
+				end = space;
+			}
+		}
+		if used > width {
+			// A grapheme wider than the column is replaced by an ellipsis.
+			lines.push((offset(start)..offset(start), true));
+		} else {
+			lines.push((offset(start)..offset(end), false));
+		}
+		start = end;
+		while start < graphemes.len() && graphemes[start].1 == " " {
+			start += 1;
+		}
+	}
+	lines
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wide_graphemes_in_narrow_columns() {
+		let column = Column::new("City");
+		assert_eq!(column.lines("東京", 1, 0), [(0..0, true)]);
+		assert_eq!(column.lines("東京", 3, 0), [(0..3, true)]);
+
+		let column = Column::new("City").overflow(Overflow::Wrap);
+		assert_eq!(column.lines("東京", 1, 0), [(0..0, true), (3..3, true)]);
+		assert_eq!(column.lines("a東", 2, 0), [(0..1, false), (1..4, false)]);
+
+		let column = Column::new("City").overflow(Overflow::Scroll);
+		assert_eq!(column.lines("東京", 1, 0), [(0..0, true)]);
+		assert_eq!(column.lines("a東京", 2, 1), [(1..4, false)]);
+		assert_eq!(column.lines("a東京", 2, 0), [(0..1, true)]);
+	}
+}
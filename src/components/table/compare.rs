@@ -0,0 +1,82 @@
+//! This module contains comparators to sort the columns of a Table.
+
+use std::cmp::Ordering;
+
+/// Compares two cells as strings, char by char.
+pub fn lexical(a: &str, b: &str) -> Ordering {
+	a.cmp(b)
+}
+
+/// Compares two cells as numbers.
+///
+/// The cells which are not numbers are placed after the numbers,
+/// and compared as strings.
+pub fn numeric(a: &str, b: &str) -> Ordering {
+	match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+		(Ok(a), Ok(b)) => a.total_cmp(&b),
+		(Ok(_), Err(_)) => Ordering::Less,
+		(Err(_), Ok(_)) => Ordering::Greater,
+		(Err(_), Err(_)) => a.cmp(b),
+	}
+}
+
+/// Compares two cells in natural order, e.g. "file2" before "file10".
+///
+/// The sequences of digits are compared as numbers, the rest of the
+/// text is compared ignoring the case.
+pub fn natural(a: &str, b: &str) -> Ordering {
+	let (chunks_a, chunks_b) = (chunks(a), chunks(b));
+	for (chunk_a, chunk_b) in chunks_a.iter().zip(&chunks_b) {
+		let digits = |chunk: &str| chunk.starts_with(|c: char| c.is_ascii_digit());
+		let ordering = if digits(chunk_a) && digits(chunk_b) {
+			let (trimmed_a, trimmed_b) = (chunk_a.trim_start_matches('0'), chunk_b.trim_start_matches('0'));
+			trimmed_a.len().cmp(&trimmed_b.len()).then_with(|| trimmed_a.cmp(trimmed_b))
+		} else {
+			chunk_a.to_lowercase().cmp(&chunk_b.to_lowercase())
+		};
+		if ordering != Ordering::Equal {
+			return ordering;
+		}
+	}
+	chunks_a.len().cmp(&chunks_b.len()).then_with(|| a.cmp(b))
+}
+
+/// Compares two cells as dates.
+///
+/// The dates must be written from the largest unit to the smallest one,
+/// as in ISO 8601 (e.g. "2024-01-31" or "2024-01-31 08:30:00"): their
+/// numbers are compared one by one. The cells without any number are
+/// placed after the dates.
+pub fn date(a: &str, b: &str) -> Ordering {
+	let numbers = |text: &str| -> Vec<u64> {
+		text.split(|c: char| !c.is_ascii_digit())
+			.filter(|part| !part.is_empty())
+			.filter_map(|part| part.parse().ok())
+			.collect()
+	};
+	let (numbers_a, numbers_b) = (numbers(a), numbers(b));
+	match (numbers_a.is_empty(), numbers_b.is_empty()) {
+		(false, true) => Ordering::Less,
+		(true, false) => Ordering::Greater,
+		_ => numbers_a.cmp(&numbers_b).then_with(|| a.cmp(b)),
+	}
+}
+
+/// Splits a text into sequences of digits and sequences of other chars.
+fn chunks(text: &str) -> Vec<&str> {
+	let mut chunks = Vec::new();
+	let mut start = 0;
+	let mut previous_digit = None;
+	for (index, c) in text.char_indices() {
+		let digit = c.is_ascii_digit();
+		if previous_digit.is_some_and(|previous| previous != digit) {
+			chunks.push(&text[start..index]);
+			start = index;
+		}
+		previous_digit = Some(digit);
+	}
+	if start < text.len() {
+		chunks.push(&text[start..]);
+	}
+	chunks
+}
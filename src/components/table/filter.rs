@@ -0,0 +1,63 @@
+//! This module contains the filters narrowing the lines of a Table.
+
+use super::pattern::Pattern;
+
+/// A function accepting the cells of a line.
+pub type LinePredicate = Box<dyn Fn(&[String]) -> bool>;
+
+/// Decides which lines of a Table are displayed.
+pub enum Filter {
+	/// The lines having a cell containing the text, ignoring the case.
+	Contains(String),
+	/// The lines having a cell matching the pattern.
+	Matches(Pattern),
+	/// The lines whose cell in the given column is accepted by the function.
+	Column(usize, Box<dyn Fn(&str) -> bool>),
+	/// The lines accepted by the function, given all their cells.
+	Lines(LinePredicate),
+}
+
+impl Filter {
+	/// Returns whether a line is accepted.
+	///
+	/// An empty text accepts all the lines.
+	pub fn accepts(&self, line: &[String]) -> bool {
+		match self {
+			Filter::Contains(text) if text.is_empty() => true,
+			Filter::Contains(_) | Filter::Matches(_) => line.iter().any(|cell| !self.matches_in(cell).is_empty()),
+			Filter::Column(column, accept) => accept(line.get(*column).map(String::as_str).unwrap_or("")),
+			Filter::Lines(accept) => accept(line),
+		}
+	}
+
+	/// Returns the byte ranges of the matches in a cell, to highlight them.
+	///
+	/// Only the texts and the patterns have matches, the functions don't.
+	pub fn matches_in(&self, cell: &str) -> Vec<(usize, usize)> {
+		match self {
+			Filter::Contains(text) => find_text(cell, text),
+			Filter::Matches(pattern) => pattern.find_all(cell)
+				.into_iter()
+				.filter(|(start, end)| end > start)
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+}
+
+/// Returns the byte ranges of a text in a cell, ignoring the case.
+pub(super) fn find_text(cell: &str, text: &str) -> Vec<(usize, usize)> {
+	if text.is_empty() {
+		return Vec::new();
+	}
+	let needle = text.to_lowercase();
+	let haystack = cell.to_lowercase();
+	if haystack.len() != cell.len() {
+		// The lowercase cell has other offsets, only tell whether it matches.
+		return if haystack.contains(&needle) { vec![(0, cell.len())] } else { Vec::new() };
+	}
+	haystack.match_indices(&needle)
+		.map(|(start, found)| (start, start + found.len()))
+		.filter(|(start, end)| cell.is_char_boundary(*start) && cell.is_char_boundary(*end))
+		.collect()
+}
@@ -0,0 +1,169 @@
+//! This module contains the formats applied to the cells of a column.
+
+use std::fmt;
+use std::rc::Rc;
+
+/// A function formatting the text of a cell.
+pub type Formatter = Rc<dyn Fn(&str) -> String>;
+
+/// How the cells of a column are displayed.
+///
+/// The cells which are not finite numbers, including nan and inf, are
+/// displayed as they are by the numeric formats. Only the display changes: the data, the sort and the filter use the cells as given.
+#[derive(Clone)]
+pub enum Format {
+	/// A number with a fixed number of decimals, and the thousands
+	/// separated by a char, if any. Example: 1,234.50
+	Number {
+		/// The number of decimals.
+		decimals: u8,
+		/// The char separating the thousands.
+		separator: Option<char>,
+	},
+	/// A number of bytes, with a binary unit. Example: 1.5 KiB
+	Bytes,
+	/// A number of seconds, with the two largest units. Example: 1h 05m
+	Duration,
+	/// A custom function.
+	Custom(Formatter),
+}
+
+impl fmt::Debug for Format {
+	/// Writes the kind of format.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Format::Number { decimals, separator } => f.debug_struct("Number")
+				.field("decimals", decimals)
+				.field("separator", separator)
+				.finish(),
+			Format::Bytes => write!(f, "Bytes"),
+			Format::Duration => write!(f, "Duration"),
+			Format::Custom(_) => write!(f, "Custom"),
+		}
+	}
+}
+
+impl Format {
+	/// Formats the text of a cell.
+	pub fn apply(&self, cell: &str) -> String {
+		match self {
+			Format::Custom(formatter) => formatter(cell),
+			Format::Number { decimals, separator } => numeric(cell, |value| number(value, *decimals, *separator)),
+			Format::Bytes => numeric(cell, bytes),
+			Format::Duration => numeric(cell, duration),
+		}
+	}
+}
+
+/// Formats a cell holding a finite number, or returns the cell as it is.
+fn numeric(cell: &str, format: impl Fn(f64) -> String) -> String {
+	match cell.trim().parse::<f64>() {
+		Ok(value) if value.is_finite() => format(value),
+		_ => cell.to_string(),
+	}
+}
+
+/// Formats a number with a number of decimals and a thousands separator.
+fn number(value: f64, decimals: u8, separator: Option<char>) -> String {
+	let text = format!("{:.*}", decimals as usize, value.abs());
+	let (integer, fraction) = match text.split_once('.') {
+		Some((integer, fraction)) => (integer, Some(fraction)),
+		None => (text.as_str(), None),
+	};
+	let mut grouped = String::new();
+	for (index, digit) in integer.chars().enumerate() {
+		if index > 0 && (integer.len() - index).is_multiple_of(3) {
+			if let Some(separator) = separator {
+				grouped.push(separator);
+			}
+		}
+		grouped.push(digit);
+	}
+	let sign = if value.is_sign_negative() && text.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+	match fraction {
+		Some(fraction) => format!("{}{}.{}", sign, grouped, fraction),
+		None => format!("{}{}", sign, grouped),
+	}
+}
+
+/// Formats a number of bytes with the largest binary unit below it.
+fn bytes(value: f64) -> String {
+	const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+	let mut size = value;
+	let mut unit = 0;
+	while size.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+	if unit == 0 {
+		format!("{} {}", size, UNITS[0])
+	} else {
+		format!("{:.1} {}", size, UNITS[unit])
+	}
+}
+
+/// Formats a number of seconds with its two largest units.
+fn duration(value: f64) -> String {
+	const UNITS: [(u64, &str); 4] = [(86_400, "d"), (3_600, "h"), (60, "m"), (1, "s")];
+	let sign = if value < 0.0 { "-" } else { "" };
+	let seconds = value.abs().round() as u64;
+	let Some(largest) = UNITS.iter().position(|(unit, _)| seconds >= *unit) else {
+		return "0s".to_string();
+	};
+	let (unit, name) = UNITS[largest];
+	match UNITS.get(largest + 1) {
+		Some((next, next_name)) if !seconds.is_multiple_of(unit) => {
+			format!("{}{}{} {:02}{}", sign, seconds / unit, name, seconds % unit / next, next_name)
+		},
+		_ => format!("{}{}{}", sign, seconds / unit, name),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn numbers() {
+		let format = Format::Number { decimals: 2, separator: Some(',') };
+		assert_eq!(format.apply("1234.5"), "1,234.50");
+		assert_eq!(format.apply(" 999 "), "999.00");
+		assert_eq!(format.apply("-0.001"), "0.00");
+		let format = Format::Number { decimals: 0, separator: Some(' ') };
+		assert_eq!(format.apply("-1234567"), "-1 234 567");
+		assert_eq!(Format::Number { decimals: 1, separator: None }.apply("1234567.25"), "1234567.2");
+	}
+
+	#[test]
+	fn bytes_and_durations() {
+		assert_eq!(Format::Bytes.apply("512"), "512 B");
+		assert_eq!(Format::Bytes.apply("1536"), "1.5 KiB");
+		assert_eq!(Format::Bytes.apply("1048576"), "1.0 MiB");
+
+		assert_eq!(Format::Duration.apply("0"), "0s");
+		assert_eq!(Format::Duration.apply("59"), "59s");
+		assert_eq!(Format::Duration.apply("3600"), "1h");
+		assert_eq!(Format::Duration.apply("3601"), "1h 00m");
+		assert_eq!(Format::Duration.apply("3660"), "1h 01m");
+		assert_eq!(Format::Duration.apply("90061"), "1d 01h");
+		assert_eq!(Format::Duration.apply("-90"), "-1m 30s");
+	}
+
+	#[test]
+	fn other_cells_are_kept() {
+		let formats = [Format::Number { decimals: 2, separator: Some(',') }, Format::Bytes, Format::Duration];
+		for format in formats {
+			for cell in ["", "n/a", "12 items", "nan", "NaN", "inf", "-inf", "infinity", "1e999"] {
+				assert_eq!(format.apply(cell), cell, "{:?}", format);
+			}
+		}
+	}
+
+	#[test]
+	fn custom_formats() {
+		let format = Format::Custom(Rc::new(|cell| format!("<{}>", cell.to_uppercase())));
+		assert_eq!(format.apply("up"), "<UP>");
+		assert_eq!(format.apply("nan"), "<NAN>");
+		assert_eq!(format!("{:?}", format), "Custom");
+	}
+}
@@ -0,0 +1,684 @@
+//! This module contains a small regular expression engine, used to
+//! filter and search the cells of a Table.
+//!
+//! The supported syntax is: literal chars, `.`, classes such as `[a-z]`
+//! or `[^0-9]`, `\d`, `\w`, `\s` and their negations, the anchors `^`
+//! and `$`, groups, alternations with `|`, and the repetitions `*`, `+`,
+//! `?`, `{n}`, `{n,}` and `{n,m}`. A leading `(?i)` ignores the case.
+//! The other escapes of letters and digits, such as the backreferences
+//! or `\p{L}`, are rejected rather than read as literal chars.
+//!
+//! The expression is compiled into a program run by a Pike virtual
+//! machine: all the ways to match are followed at once, char after char,
+//! so the time taken only grows with the length of the text times the
+//! size of the program, and never with backtracking. The size of the
+//! program, the repetition counts and the nesting of the groups are
+//! limited.
+
+use std::fmt;
+
+/// The maximum number of instructions of a compiled expression.
+const MAX_PROGRAM: usize = 10_000;
+/// The maximum count of a repetition such as `{n,m}`.
+const MAX_REPEAT: usize = 1_000;
+/// The maximum number of groups opened inside each other.
+const MAX_NESTING: usize = 100;
+
+/// A compiled regular expression.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+	/// The expression, as given.
+	source: String,
+	/// The instructions of the compiled expression.
+	program: Vec<Instruction>,
+	/// Whether the case is ignored.
+	ignore_case: bool,
+}
+
+/// The error returned when an expression can't be compiled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+	/// The position of the char where the error has been found.
+	pub position: usize,
+	/// What is wrong.
+	pub message: &'static str,
+}
+
+impl fmt::Display for PatternError {
+	/// Writes the message and the position.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at position {}", self.message, self.position)
+	}
+}
+
+impl std::error::Error for PatternError {}
+
+/// A node of a compiled expression.
+#[derive(Clone, Debug)]
+enum Node {
+	/// A single char.
+	Char(char),
+	/// Any char.
+	Any,
+	/// A char in, or out of, the given ranges.
+	Class(Vec<(char, char)>, bool),
+	/// The start of the text.
+	Start,
+	/// The end of the text.
+	End,
+	/// Nodes matching one after the other.
+	Concat(Vec<Node>),
+	/// Nodes matching at the same place, the first ones preferred.
+	Alternation(Vec<Node>),
+	/// A node repeated between a minimum and an optional maximum of times.
+	Repeat(Box<Node>, usize, Option<usize>),
+}
+
+/// An instruction of a compiled expression.
+#[derive(Clone, Debug)]
+enum Instruction {
+	/// Reads the given char.
+	Char(char),
+	/// Reads any char.
+	Any,
+	/// Reads a char in, or out of, the given ranges.
+	Class(Vec<(char, char)>, bool),
+	/// Goes on only at the start of the text.
+	Start,
+	/// Goes on only at the end of the text.
+	End,
+	/// Goes on at both instructions, the first one preferred.
+	Split(usize, usize),
+	/// Goes on at the instruction.
+	Jump(usize),
+	/// The expression matched.
+	Match,
+}
+
+impl Pattern {
+	/// Compiles an expression.
+	pub fn new(source: &str) -> Result<Self, PatternError> {
+		let (ignore_case, expression) = match source.strip_prefix("(?i)") {
+			Some(rest) => (true, rest),
+			None => (false, source),
+		};
+		let mut parser = Parser {
+			chars: expression.chars().collect(),
+			position: 0,
+			depth: 0,
+		};
+		let root = parser.alternation()?;
+		if parser.position < parser.chars.len() {
+			return Err(parser.error("unmatched closing parenthesis"));
+		}
+		let mut program = Vec::new();
+		if compile(&root, ignore_case, &mut program).is_none() {
+			return Err(PatternError { position: 0, message: "expression too large" });
+		}
+		program.push(Instruction::Match);
+		Ok(Self {
+			source: source.to_string(),
+			program,
+			ignore_case,
+		})
+	}
+
+	/// Returns the expression, as given.
+	pub fn as_str(&self) -> &str {
+		&self.source
+	}
+
+	/// Returns whether the expression matches somewhere in the text.
+	pub fn is_match(&self, text: &str) -> bool {
+		self.find(text).is_some()
+	}
+
+	/// Returns the byte range of the first match in the text.
+	pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+		let (chars, offsets) = self.prepare(text);
+		Machine::new(self, chars.len())
+			.search(&chars, 0)
+			.map(|(start, end)| (offsets[start], offsets[end]))
+	}
+
+	/// Returns the byte ranges of all the matches in the text, which
+	/// don't overlap.
+	pub fn find_all(&self, text: &str) -> Vec<(usize, usize)> {
+		let (chars, offsets) = self.prepare(text);
+		let mut machine = Machine::new(self, chars.len());
+		let mut matches = Vec::new();
+		let mut start = 0;
+		while start <= chars.len() {
+			let Some((match_start, match_end)) = machine.search(&chars, start) else {
+				break;
+			};
+			matches.push((offsets[match_start], offsets[match_end]));
+			start = if match_end > match_start { match_end } else { match_end + 1 };
+		}
+		matches
+	}
+
+	/// Returns the chars the expression is run on, lowercase when the
+	/// case is ignored, and the byte offset of each char followed by the
+	/// length of the text.
+	fn prepare(&self, text: &str) -> (Vec<char>, Vec<usize>) {
+		let chars = if self.ignore_case {
+			text.chars().map(lowercase).collect()
+		} else {
+			text.chars().collect()
+		};
+		let offsets = text.char_indices()
+			.map(|(offset, _)| offset)
+			.chain(std::iter::once(text.len()))
+			.collect();
+		(chars, offsets)
+	}
+}
+
+/// Returns the lowercase version of a char, when it's a single char.
+fn lowercase(c: char) -> char {
+	let mut lower = c.to_lowercase();
+	match (lower.next(), lower.next()) {
+		(Some(lower), None) => lower,
+		_ => c,
+	}
+}
+
+/// Returns whether a char is in a class.
+fn class_contains(ranges: &[(char, char)], negated: bool, c: char, ignore_case: bool) -> bool {
+	let inside = ranges.iter().any(|(low, high)| {
+		(*low..=*high).contains(&c)
+			|| (ignore_case && c.is_alphabetic() && (*low..=*high).contains(&c.to_uppercase().next().unwrap_or(c)))
+	});
+	inside != negated
+}
+
+/// Compiles a node at the end of a program.
+///
+/// Returns None when the program gets larger than MAX_PROGRAM.
+fn compile(node: &Node, ignore_case: bool, program: &mut Vec<Instruction>) -> Option<()> {
+	match node {
+		Node::Char(c) => program.push(Instruction::Char(if ignore_case { lowercase(*c) } else { *c })),
+		Node::Any => program.push(Instruction::Any),
+		Node::Class(ranges, negated) => program.push(Instruction::Class(ranges.clone(), *negated)),
+		Node::Start => program.push(Instruction::Start),
+		Node::End => program.push(Instruction::End),
+		Node::Concat(nodes) => {
+			for node in nodes {
+				compile(node, ignore_case, program)?;
+			}
+		},
+		Node::Alternation(nodes) => {
+			// Each branch but the last one is preceded by a split to the
+			// next branch, and followed by a jump to the end.
+			let mut jumps = Vec::new();
+			for (index, node) in nodes.iter().enumerate() {
+				if index + 1 < nodes.len() {
+					let split = program.len();
+					program.push(Instruction::Split(split + 1, 0));
+					compile(node, ignore_case, program)?;
+					jumps.push(program.len());
+					program.push(Instruction::Jump(0));
+					program[split] = Instruction::Split(split + 1, program.len());
+				} else {
+					compile(node, ignore_case, program)?;
+				}
+			}
+			let end = program.len();
+			for jump in jumps {
+				program[jump] = Instruction::Jump(end);
+			}
+		},
+		Node::Repeat(node, min, max) => {
+			for _ in 0..*min {
+				compile(node, ignore_case, program)?;
+			}
+			match max {
+				None => {
+					let split = program.len();
+					program.push(Instruction::Split(split + 1, 0));
+					compile(node, ignore_case, program)?;
+					program.push(Instruction::Jump(split));
+					program[split] = Instruction::Split(split + 1, program.len());
+				},
+				Some(max) => {
+					// Each optional repetition may be skipped to the end.
+					let mut splits = Vec::new();
+					for _ in *min..*max {
+						splits.push(program.len());
+						program.push(Instruction::Split(program.len() + 1, 0));
+						compile(node, ignore_case, program)?;
+					}
+					let end = program.len();
+					for split in splits {
+						program[split] = Instruction::Split(split + 1, end);
+					}
+				},
+			}
+		},
+	}
+	(program.len() <= MAX_PROGRAM).then_some(())
+}
+
+/// Runs a compiled expression on a text, following all the ways to match
+/// at once.
+struct Machine<'a> {
+	/// The instructions.
+	program: &'a [Instruction],
+	/// Whether the case is ignored.
+	ignore_case: bool,
+	/// The number of chars of the text.
+	length: usize,
+	/// The position each instruction has been reached at for the last
+	/// time, so that each instruction runs once per position.
+	marks: Vec<usize>,
+	/// The instructions left to follow, when adding a thread.
+	stack: Vec<usize>,
+}
+
+impl<'a> Machine<'a> {
+	/// Creates a new Machine for a text of the given length.
+	fn new(pattern: &'a Pattern, length: usize) -> Self {
+		Self {
+			program: &pattern.program,
+			ignore_case: pattern.ignore_case,
+			length,
+			marks: vec![usize::MAX; pattern.program.len()],
+			stack: Vec::new(),
+		}
+	}
+
+	/// Returns the char range of the first match starting from a char
+	/// position: the leftmost one, and among them the one a backtracking
+	/// engine would find first.
+	fn search(&mut self, text: &[char], from: usize) -> Option<(usize, usize)> {
+		self.marks.fill(usize::MAX);
+		// The threads are the instructions reading a char, with the start
+		// of their match, the preferred ones first.
+		let mut current: Vec<(usize, usize)> = Vec::new();
+		let mut next: Vec<(usize, usize)> = Vec::new();
+		let mut found = None;
+		for position in from..=self.length {
+			if found.is_none() {
+				self.add(&mut current, 0, position, position);
+			}
+			if current.is_empty() {
+				break;
+			}
+			let c = text.get(position).copied();
+			for &(pc, start) in &current {
+				let read = match (&self.program[pc], c) {
+					(Instruction::Match, _) => {
+						// The threads after this one are less preferred.
+						found = Some((start, position));
+						break;
+					},
+					(Instruction::Char(expected), Some(c)) => *expected == c,
+					(Instruction::Any, Some(_)) => true,
+					(Instruction::Class(ranges, negated), Some(c)) => class_contains(ranges, *negated, c, self.ignore_case),
+					_ => false,
+				};
+				if read {
+					self.add(&mut next, pc + 1, start, position + 1);
+				}
+			}
+			std::mem::swap(&mut current, &mut next);
+			next.clear();
+		}
+		found
+	}
+
+	/// Adds the threads reached from an instruction, following the
+	/// splits, the jumps and the anchors, in order of preference.
+	///
+	/// # Parameters
+	/// - threads: where the threads are added.
+	/// - pc: the instruction.
+	/// - start: the start of the match of the threads.
+	/// - position: the position of the next char to read.
+	fn add(&mut self, threads: &mut Vec<(usize, usize)>, pc: usize, start: usize, position: usize) {
+		self.stack.push(pc);
+		while let Some(pc) = self.stack.pop() {
+			if self.marks[pc] == position {
+				continue;
+			}
+			self.marks[pc] = position;
+			match &self.program[pc] {
+				Instruction::Jump(target) => self.stack.push(*target),
+				Instruction::Split(first, second) => {
+					self.stack.push(*second);
+					self.stack.push(*first);
+				},
+				Instruction::Start => {
+					if position == 0 {
+						self.stack.push(pc + 1);
+					}
+				},
+				Instruction::End => {
+					if position == self.length {
+						self.stack.push(pc + 1);
+					}
+				},
+				_ => threads.push((pc, start)),
+			}
+		}
+	}
+}
+
+/// Compiles an expression into nodes.
+struct Parser {
+	/// The chars of the expression.
+	chars: Vec<char>,
+	/// The position of the next char to read.
+	position: usize,
+	/// The number of groups opened around the position.
+	depth: usize,
+}
+
+impl Parser {
+	/// Builds an error at the current position.
+	fn error(&self, message: &'static str) -> PatternError {
+		PatternError { position: self.position, message }
+	}
+
+	/// Returns the next char without reading it.
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.position).copied()
+	}
+
+	/// Reads the next char.
+	fn read(&mut self) -> Option<char> {
+		let c = self.peek();
+		self.position += 1;
+		c
+	}
+
+	/// Parses branches separated by `|`.
+	fn alternation(&mut self) -> Result<Node, PatternError> {
+		let mut branches = vec![self.concat()?];
+		while self.peek() == Some('|') {
+			self.position += 1;
+			branches.push(self.concat()?);
+		}
+		Ok(if branches.len() == 1 { branches.remove(0) } else { Node::Alternation(branches) })
+	}
+
+	/// Parses repeated atoms until the end of a branch.
+	fn concat(&mut self) -> Result<Node, PatternError> {
+		let mut nodes = Vec::new();
+		while let Some(c) = self.peek() {
+			if c == '|' || c == ')' {
+				break;
+			}
+			let atom = self.atom()?;
+			nodes.push(self.repetition(atom)?);
+		}
+		Ok(Node::Concat(nodes))
+	}
+
+	/// Parses the repetition following an atom, if any.
+	fn repetition(&mut self, atom: Node) -> Result<Node, PatternError> {
+		let (min, max) = match self.peek() {
+			Some('*') => (0, None),
+			Some('+') => (1, None),
+			Some('?') => (0, Some(1)),
+			Some('{') => {
+				self.position += 1;
+				let min = self.number().ok_or_else(|| self.error("expected a number"))?;
+				let max = match self.peek() {
+					Some('}') => Some(min),
+					Some(',') => {
+						self.position += 1;
+						self.number()
+					},
+					_ => return Err(self.error("expected a comma or a closing brace")),
+				};
+				if self.peek() != Some('}') {
+					return Err(self.error("expected a closing brace"));
+				}
+				(min, max)
+			},
+			_ => return Ok(atom),
+		};
+		if matches!(atom, Node::Start | Node::End) || max.is_some_and(|max| max < min) {
+			return Err(self.error("invalid repetition"));
+		}
+		if min > MAX_REPEAT || max.is_some_and(|max| max > MAX_REPEAT) {
+			return Err(self.error("repetition count too large"));
+		}
+		self.position += 1;
+		Ok(Node::Repeat(Box::new(atom), min, max))
+	}
+
+	/// Parses a number, for the repetitions.
+	fn number(&mut self) -> Option<usize> {
+		let start = self.position;
+		while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+			self.position += 1;
+		}
+		self.chars[start..self.position].iter().collect::<String>().parse().ok()
+	}
+
+	/// Parses a single element of the expression.
+	fn atom(&mut self) -> Result<Node, PatternError> {
+		match self.read() {
+			Some('(') => {
+				self.depth += 1;
+				if self.depth > MAX_NESTING {
+					return Err(self.error("too many nested groups"));
+				}
+				let node = self.alternation()?;
+				if self.read() != Some(')') {
+					return Err(self.error("unclosed parenthesis"));
+				}
+				self.depth -= 1;
+				Ok(node)
+			},
+			Some('[') => self.class(),
+			Some('.') => Ok(Node::Any),
+			Some('^') => Ok(Node::Start),
+			Some('$') => Ok(Node::End),
+			Some('\\') => self.escape(),
+			Some('*' | '+' | '?' | '{') => Err(self.error("nothing to repeat")),
+			Some(c) => Ok(Node::Char(c)),
+			None => Err(self.error("unexpected end")),
+		}
+	}
+
+	/// Parses what follows a backslash.
+	fn escape(&mut self) -> Result<Node, PatternError> {
+		let c = self.read().ok_or_else(|| self.error("unexpected end after a backslash"))?;
+		Ok(match c {
+			'd' | 'w' | 's' => Node::Class(shorthand(c), false),
+			'D' | 'W' | 'S' => Node::Class(shorthand(c.to_ascii_lowercase()), true),
+			'n' => Node::Char('\n'),
+			't' => Node::Char('\t'),
+			other if other.is_alphanumeric() => return Err(self.error("unsupported escape")),
+			other => Node::Char(other),
+		})
+	}
+
+	/// Parses a class, after the opening bracket.
+	fn class(&mut self) -> Result<Node, PatternError> {
+		let negated = self.peek() == Some('^');
+		if negated {
+			self.position += 1;
+		}
+		let mut ranges = Vec::new();
+		let mut first = true;
+		loop {
+			let c = self.read().ok_or_else(|| self.error("unclosed bracket"))?;
+			match c {
+				']' if !first => break,
+				'\\' => match self.read() {
+					Some(kind @ ('d' | 'w' | 's')) => ranges.extend(shorthand(kind)),
+					Some('n') => ranges.push(('\n', '\n')),
+					Some('t') => ranges.push(('\t', '\t')),
+					Some(other) if other.is_alphanumeric() => return Err(self.error("unsupported escape")),
+					Some(other) => ranges.push((other, other)),
+					None => return Err(self.error("unclosed bracket")),
+				},
+				low => {
+					let high = match (self.peek(), self.chars.get(self.position + 1)) {
+						(Some('-'), Some(high)) if *high != ']' => {
+							self.position += 2;
+							*high
+						},
+						_ => low,
+					};
+					if high < low {
+						return Err(self.error("invalid range"));
+					}
+					ranges.push((low, high));
+				},
+			}
+			first = false;
+		}
+		Ok(Node::Class(ranges, negated))
+	}
+}
+
+/// Returns the ranges of the classes \d, \w and \s.
+fn shorthand(kind: char) -> Vec<(char, char)> {
+	match kind {
+		'd' => vec![('0', '9')],
+		'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+		_ => vec![(' ', ' '), ('\t', '\r')],
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::{Duration, Instant};
+
+	/// Returns the texts of all the matches.
+	fn matches<'a>(pattern: &str, text: &'a str) -> Vec<&'a str> {
+		Pattern::new(pattern).unwrap()
+			.find_all(text)
+			.into_iter()
+			.map(|(start, end)| &text[start..end])
+			.collect()
+	}
+
+	/// Returns the message of the error of an expression.
+	fn error(pattern: &str) -> &'static str {
+		Pattern::new(pattern).unwrap_err().message
+	}
+
+	#[test]
+	fn unsupported_syntax_is_rejected() {
+		for pattern in ["(a)\\1", "a(?=b)", "(?<=a)b", "\\p{L}", "[\\p{L}]", "a*?", "a+?", "(?m)^a", "(?:a)"] {
+			assert!(Pattern::new(pattern).is_err(), "{}", pattern);
+		}
+		assert_eq!(error("(a)\\1"), "unsupported escape");
+		assert_eq!(matches("\\.\\(\\[", "a.([b"), [".(["]);
+	}
+
+	#[test]
+	fn literals_and_classes() {
+		assert_eq!(matches("ab", "xabyab"), ["ab", "ab"]);
+		assert_eq!(matches("a.c", "abc a\nc"), ["abc", "a\nc"]);
+		assert_eq!(matches("[a-c]", "dcx"), ["c"]);
+		assert_eq!(matches("[^0-9]+", "12ab3"), ["ab"]);
+		assert_eq!(matches("[\\d_]+", "a1_2 b"), ["1_2"]);
+		assert_eq!(matches("[]a]", "]"), ["]"]);
+		assert_eq!(matches("\\d+", "a12 345"), ["12", "345"]);
+		assert_eq!(matches("\\w+", "ab_1-c"), ["ab_1", "c"]);
+		assert_eq!(matches("\\s", "a b\tc"), [" ", "\t"]);
+		assert_eq!(matches("\\D\\W\\S", "a-b"), ["a-b"]);
+		assert_eq!(matches("\\.\\t", "a.\t"), [".\t"]);
+		assert_eq!(matches("東.", "東京"), ["東京"]);
+	}
+
+	#[test]
+	fn anchors_and_alternations() {
+		assert_eq!(matches("^a", "aa"), ["a"]);
+		assert_eq!(matches("a$", "aa"), ["a"]);
+		assert_eq!(matches("^$", ""), [""]);
+		assert!(!Pattern::new("^b").unwrap().is_match("ab"));
+		assert_eq!(matches("cat|dog", "dog cat"), ["dog", "cat"]);
+		// The first branch is preferred, as in a backtracking engine.
+		assert_eq!(matches("a|ab", "ab"), ["a"]);
+		assert_eq!(matches("(ab|a)c", "ac abc"), ["ac", "abc"]);
+		assert_eq!(matches("x(|y)", "xy"), ["x"]);
+		assert_eq!(matches("x(y|)", "xy"), ["xy"]);
+	}
+
+	#[test]
+	fn repetitions() {
+		assert_eq!(matches("a*", "baa"), ["", "aa", ""]);
+		assert_eq!(matches("a+", "baa"), ["aa"]);
+		assert_eq!(matches("ab?c", "ac abc"), ["ac", "abc"]);
+		assert_eq!(matches("a{2}", "aaaaa"), ["aa", "aa"]);
+		assert_eq!(matches("a{2,}", "a aaaa"), ["aaaa"]);
+		assert_eq!(matches("a{1,2}", "aaa"), ["aa", "a"]);
+		assert_eq!(matches("(ab)+", "ababa"), ["abab"]);
+		assert_eq!(matches("a.*b", "a1b2b"), ["a1b2b"]);
+		assert_eq!(matches("(a*)*b", "aab"), ["aab"]);
+		assert_eq!(matches("(a|)+b", "ab"), ["ab"]);
+	}
+
+	#[test]
+	fn ignoring_the_case() {
+		assert_eq!(matches("(?i)ab", "AB aB"), ["AB", "aB"]);
+		assert_eq!(matches("(?i)[a-c]+", "xAbC"), ["AbC"]);
+		assert_eq!(matches("(?i)É", "é"), ["é"]);
+		assert!(!Pattern::new("ab").unwrap().is_match("AB"));
+	}
+
+	#[test]
+	fn byte_offsets() {
+		let pattern = Pattern::new("京").unwrap();
+		assert_eq!(pattern.find("東京"), Some((3, 6)));
+		assert_eq!(pattern.as_str(), "京");
+	}
+
+	#[test]
+	fn errors() {
+		assert_eq!(error("a)"), "unmatched closing parenthesis");
+		assert_eq!(error("(a"), "unclosed parenthesis");
+		assert_eq!(error("[a"), "unclosed bracket");
+		assert_eq!(error("[b-a]"), "invalid range");
+		assert_eq!(error("*"), "nothing to repeat");
+		assert_eq!(error("^*"), "invalid repetition");
+		assert_eq!(error("a{2,1}"), "invalid repetition");
+		assert_eq!(error("a{x}"), "expected a number");
+		assert_eq!(error("a{1"), "expected a comma or a closing brace");
+		assert_eq!(error("a{1,2"), "expected a closing brace");
+		assert_eq!(error("a\\"), "unexpected end after a backslash");
+		assert_eq!(error("a{1001}"), "repetition count too large");
+		assert_eq!(error("(a{1000}){1000}"), "expression too large");
+		assert_eq!(error(&"(".repeat(1000)), "too many nested groups");
+		assert_eq!(Pattern::new("a)").unwrap_err().position, 1);
+	}
+
+	/// Asserts that a match takes less than a second.
+	fn quickly(pattern: &str, text: &str, expected: Option<(usize, usize)>) {
+		let start = Instant::now();
+		assert_eq!(Pattern::new(pattern).unwrap().find(text), expected);
+		assert!(start.elapsed() < Duration::from_secs(1), "{} took {:?}", pattern, start.elapsed());
+	}
+
+	#[test]
+	fn pathological_patterns() {
+		let text = format!("{}b", "a".repeat(25));
+		quickly("(a+)+$", &text, None);
+		quickly("(a*)*c", &text, None);
+		quickly("(a|aa)+$", &text, None);
+		quickly("(a|a)*c", &text, None);
+		let text = "x".repeat(20_000);
+		quickly("x*y", &text, None);
+		quickly("x*", &text, Some((0, 20_000)));
+		let matches = Pattern::new("x*y").unwrap().find_all(&"x".repeat(2_000));
+		assert!(matches.is_empty());
+	}
+
+	#[test]
+	fn long_texts() {
+		let text = "ab".repeat(50_000);
+		quickly("(ab)+", &text, Some((0, 100_000)));
+		quickly("(a|b)*$", &text, Some((0, 100_000)));
+		quickly(".*c", &text, None);
+	}
+}
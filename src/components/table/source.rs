@@ -0,0 +1,63 @@
+//! This module contains the sources the lines of a Table are pulled from.
+
+use std::ops::Range;
+
+/// Provides the lines of a Table.
+///
+/// The Table only fetches the lines it displays, so the lines don't
+/// have to be in memory, e.g. a large log file or a database cursor.
+pub trait TableSource {
+	/// Returns the headers of the columns.
+	fn headers(&self) -> Vec<String>;
+	/// Returns the number of lines.
+	fn row_count(&self) -> usize;
+	/// Returns the cells of a range of lines.
+	///
+	/// The range is always within the number of lines.
+	fn fetch(&mut self, rows: Range<usize>) -> Vec<Vec<String>>;
+}
+
+/// A source keeping all the lines in memory.
+pub struct VecSource {
+	/// The line of headers.
+	headers: Vec<String>,
+	/// The lines of data.
+	rows: Vec<Vec<String>>,
+}
+
+impl VecSource {
+	/// Creates a new source from its headers and lines.
+	pub fn new(headers: &[String], rows: Vec<Vec<String>>) -> Self {
+		Self {
+			headers: headers.to_vec(),
+			rows,
+		}
+	}
+
+	/// Returns the lines of data.
+	pub fn rows(&self) -> &Vec<Vec<String>> {
+		&self.rows
+	}
+}
+
+/// Returns a cell of a line, or an empty text when the line is too short.
+pub(super) fn cell(line: &[String], column: usize) -> &str {
+	line.get(column).map(String::as_str).unwrap_or("")
+}
+
+impl TableSource for VecSource {
+	/// Returns the headers of the columns.
+	fn headers(&self) -> Vec<String> {
+		self.headers.clone()
+	}
+
+	/// Returns the number of lines.
+	fn row_count(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// Returns a copy of a range of lines.
+	fn fetch(&mut self, rows: Range<usize>) -> Vec<Vec<String>> {
+		self.rows[rows].to_vec()
+	}
+}
@@ -0,0 +1,988 @@
+//! The config module reads the configuration files, written in TOML or
+//! JSON, or in YAML with the yaml feature.
+//!
+//! A value is a string, an integer, a float, a boolean, an array or a
+//! table. The JSON and YAML null are rejected.
+//!
+//! With the serde feature, the documents are parsed by the toml and
+//! serde_json crates, the TOML dates being read as strings, and a Value
+//! can be read from any format of serde. Otherwise, a small parser
+//! supports only what the configuration of the UI needs: the TOML dates,
+//! multi-line strings and integers in other bases than 10 are rejected.
+
+#[cfg(feature = "serde")]
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::Error;
+
+/// A value read from a configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	/// A string.
+	String(String),
+	/// An integer.
+	Integer(i64),
+	/// A number with a fractional part or an exponent.
+	Float(f64),
+	/// A boolean.
+	Boolean(bool),
+	/// A list of values.
+	Array(Vec<Value>),
+	/// Values by key, in the order of the file.
+	Table(Vec<(String, Value)>),
+}
+
+impl Value {
+	/// Parses a TOML document.
+	///
+	/// Returns the root table.
+	#[cfg(not(feature = "serde"))]
+	pub fn parse_toml(text: &str) -> Result<Value, Error> {
+		let mut parser = Parser::new(text);
+		let mut root = Vec::new();
+		let mut current: Vec<String> = Vec::new();
+		// The tables defined by a header, which can't be defined again.
+		let mut defined: Vec<Vec<String>> = Vec::new();
+		loop {
+			parser.skip_gaps();
+			match parser.peek() {
+				None => break,
+				Some('[') => {
+					parser.next();
+					let array = parser.eat('[');
+					parser.skip_blanks();
+					let keys = parser.toml_key()?;
+					parser.skip_blanks();
+					parser.expect(']')?;
+					if array {
+						parser.expect(']')?;
+						append_table(&mut root, &keys, parser.line)?;
+						// The new table of the array has its own subtables.
+						defined.retain(|path| !path.starts_with(&keys));
+					} else {
+						if defined.contains(&keys) {
+							return Err(parser.error(format!("table '{}' defined twice", keys.join("."))));
+						}
+						table_at(&mut root, &keys, parser.line)?;
+						defined.push(keys.clone());
+					}
+					current = keys;
+				},
+				Some(_) => {
+					let keys = parser.toml_key()?;
+					parser.skip_blanks();
+					parser.expect('=')?;
+					parser.skip_blanks();
+					let value = parser.toml_value()?;
+					let table = table_at(&mut root, &current, parser.line)?;
+					insert(table, &keys, value, parser.line)?;
+				},
+			}
+			parser.end_of_line()?;
+		}
+		Ok(Value::Table(root))
+	}
+
+	/// Parses a JSON document.
+	#[cfg(not(feature = "serde"))]
+	pub fn parse_json(text: &str) -> Result<Value, Error> {
+		let mut parser = Parser::new(text);
+		let value = parser.json_value()?;
+		parser.skip_whitespace();
+		match parser.peek() {
+			None => Ok(value),
+			Some(c) => Err(parser.error(format!("unexpected '{}' after the value", c))),
+		}
+	}
+
+	/// Parses a TOML document, with the toml crate.
+	///
+	/// Returns the root table. The dates are read as strings.
+	#[cfg(feature = "serde")]
+	pub fn parse_toml(text: &str) -> Result<Value, Error> {
+		let table: toml::Table = toml::from_str(text).map_err(|error| Error::Parse {
+			line: error.span().map_or(1, |span| text[..span.start].matches('\n').count() + 1),
+			message: error.message().to_string(),
+		})?;
+		Ok(Value::from(toml::Value::Table(table)))
+	}
+
+	/// Parses a JSON document, with the serde_json crate.
+	#[cfg(feature = "serde")]
+	pub fn parse_json(text: &str) -> Result<Value, Error> {
+		serde_json::from_str(text).map_err(|error| Error::Parse {
+			line: error.line().max(1),
+			message: error.to_string(),
+		})
+	}
+
+	/// Parses a YAML document.
+	#[cfg(feature = "yaml")]
+	pub fn parse_yaml(text: &str) -> Result<Value, Error> {
+		serde_yaml::from_str(text).map_err(|error| Error::Parse {
+			line: error.location().map_or(1, |location| location.line()),
+			message: error.to_string(),
+		})
+	}
+
+	/// Reads and parses a configuration file.
+	///
+	/// Files ending with .json are parsed as JSON, the ones ending with
+	/// .yaml or .yml as YAML with the yaml feature, the other ones as TOML.
+	pub fn load(path: &Path) -> Result<Value, Error> {
+		let text = fs::read_to_string(path)
+			.map_err(|error| Error::Io { path: path.to_path_buf(), error })?;
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some(extension) if extension.eq_ignore_ascii_case("json") => Value::parse_json(&text),
+			#[cfg(feature = "yaml")]
+			Some(extension) if extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml") => {
+				Value::parse_yaml(&text)
+			},
+			_ => Value::parse_toml(&text),
+		}
+	}
+
+	/// Returns the value of a key, when the value is a table.
+	pub fn get(&self, key: &str) -> Option<&Value> {
+		self.as_table()?.iter()
+			.find(|(name, _)| name == key)
+			.map(|(_, value)| value)
+	}
+
+	/// Returns the string, if the value is one.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Value::String(text) => Some(text),
+			_ => None,
+		}
+	}
+
+	/// Returns the integer, if the value is one.
+	pub fn as_integer(&self) -> Option<i64> {
+		match self {
+			Value::Integer(integer) => Some(*integer),
+			_ => None,
+		}
+	}
+
+	/// Returns the number, if the value is a float or an integer.
+	pub fn as_float(&self) -> Option<f64> {
+		match self {
+			Value::Float(float) => Some(*float),
+			Value::Integer(integer) => Some(*integer as f64),
+			_ => None,
+		}
+	}
+
+	/// Returns the boolean, if the value is one.
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Value::Boolean(boolean) => Some(*boolean),
+			_ => None,
+		}
+	}
+
+	/// Returns the values, if the value is an array.
+	pub fn as_array(&self) -> Option<&[Value]> {
+		match self {
+			Value::Array(values) => Some(values),
+			_ => None,
+		}
+	}
+
+	/// Returns the keys and their values, if the value is a table.
+	pub fn as_table(&self) -> Option<&[(String, Value)]> {
+		match self {
+			Value::Table(entries) => Some(entries),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl From<toml::Value> for Value {
+	/// Converts a TOML value, the dates becoming strings.
+	fn from(value: toml::Value) -> Self {
+		match value {
+			toml::Value::String(text) => Value::String(text),
+			toml::Value::Integer(integer) => Value::Integer(integer),
+			toml::Value::Float(float) => Value::Float(float),
+			toml::Value::Boolean(boolean) => Value::Boolean(boolean),
+			toml::Value::Datetime(date) => Value::String(date.to_string()),
+			toml::Value::Array(values) => Value::Array(values.into_iter().map(Value::from).collect()),
+			toml::Value::Table(entries) => Value::Table(entries.into_iter().map(|(key, value)| (key, Value::from(value))).collect()),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Value {
+	/// Reads a value from any format of serde, the null values being rejected.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ValueVisitor)
+	}
+}
+
+/// Builds a Value from what a format of serde reads.
+#[cfg(feature = "serde")]
+struct ValueVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+	type Value = Value;
+
+	/// Writes what is expected.
+	fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "a string, a number, a boolean, an array or a table")
+	}
+
+	/// Reads a boolean.
+	fn visit_bool<E: serde::de::Error>(self, value: bool) -> Result<Value, E> {
+		Ok(Value::Boolean(value))
+	}
+
+	/// Reads an integer.
+	fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Value, E> {
+		Ok(Value::Integer(value))
+	}
+
+	/// Reads a positive integer, which must fit in an i64.
+	fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Value, E> {
+		i64::try_from(value)
+			.map(Value::Integer)
+			.map_err(|_| E::custom(format!("invalid number '{}'", value)))
+	}
+
+	/// Reads a float.
+	fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Value, E> {
+		Ok(Value::Float(value))
+	}
+
+	/// Reads a string.
+	fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Value, E> {
+		Ok(Value::String(value.to_string()))
+	}
+
+	/// Rejects null.
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Value, E> {
+		Err(E::custom("null is not supported"))
+	}
+
+	/// Reads an array.
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut sequence: A) -> Result<Value, A::Error> {
+		let mut values = Vec::new();
+		while let Some(value) = sequence.next_element()? {
+			values.push(value);
+		}
+		Ok(Value::Array(values))
+	}
+
+	/// Reads a table, in the order of the document.
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+		let mut entries: Vec<(String, Value)> = Vec::new();
+		while let Some((key, value)) = map.next_entry::<String, Value>()? {
+			if entries.iter().any(|(name, _)| *name == key) {
+				return Err(serde::de::Error::custom(format!("duplicate key '{}'", key)));
+			}
+			entries.push((key, value));
+		}
+		Ok(Value::Table(entries))
+	}
+}
+
+/// Returns the table at the end of a path of keys, creating the missing
+/// ones. The last table of an array of tables is used for the arrays.
+#[cfg(not(feature = "serde"))]
+fn table_at<'a>(table: &'a mut Vec<(String, Value)>, keys: &[String], line: usize) -> Result<&'a mut Vec<(String, Value)>, Error> {
+	let Some((key, rest)) = keys.split_first() else {
+		return Ok(table);
+	};
+	let index = match table.iter().position(|(name, _)| name == key) {
+		Some(index) => index,
+		None => {
+			table.push((key.clone(), Value::Table(Vec::new())));
+			table.len() - 1
+		},
+	};
+	let child = match &mut table[index].1 {
+		Value::Table(child) => child,
+		Value::Array(values) => match values.last_mut() {
+			Some(Value::Table(child)) => child,
+			_ => return Err(Error::Parse { line, message: format!("'{}' is not a table", key) }),
+		},
+		_ => return Err(Error::Parse { line, message: format!("'{}' is not a table", key) }),
+	};
+	table_at(child, rest, line)
+}
+
+/// Adds a new table at the end of an array of tables, creating the array
+/// when it's missing.
+#[cfg(not(feature = "serde"))]
+fn append_table(root: &mut Vec<(String, Value)>, keys: &[String], line: usize) -> Result<(), Error> {
+	let Some((key, parents)) = keys.split_last() else {
+		return Err(Error::Parse { line, message: "missing key".to_string() });
+	};
+	let table = table_at(root, parents, line)?;
+	match table.iter_mut().find(|(name, _)| name == key) {
+		None => table.push((key.clone(), Value::Array(vec![Value::Table(Vec::new())]))),
+		Some((_, Value::Array(values))) => values.push(Value::Table(Vec::new())),
+		Some(_) => return Err(Error::Parse { line, message: format!("'{}' is not an array of tables", key) }),
+	}
+	Ok(())
+}
+
+/// Adds a value in a table, the dotted keys creating the tables in between.
+#[cfg(not(feature = "serde"))]
+fn insert(table: &mut Vec<(String, Value)>, keys: &[String], value: Value, line: usize) -> Result<(), Error> {
+	let Some((key, parents)) = keys.split_last() else {
+		return Err(Error::Parse { line, message: "missing key".to_string() });
+	};
+	let table = table_at(table, parents, line)?;
+	if table.iter().any(|(name, _)| name == key) {
+		return Err(Error::Parse { line, message: format!("duplicate key '{}'", key) });
+	}
+	table.push((key.clone(), value));
+	Ok(())
+}
+
+/// The maximum number of arrays and tables inside each other.
+#[cfg(not(feature = "serde"))]
+const MAX_DEPTH: usize = 100;
+
+/// Returns whether a number is well written: an integer part without
+/// leading zeros, then optionally a fraction and an exponent, each with at
+/// least a digit. In TOML, the number can start with a + and the digits
+/// can be separated by single underscores.
+#[cfg(not(feature = "serde"))]
+fn valid_number(text: &str, toml: bool) -> bool {
+	// Returns the number of chars of the digits starting the text, and the
+	// rest of the text, when there is a digit and no trailing underscore.
+	let digits = |text: &str| -> Option<(usize, String)> {
+		let mut count = 0;
+		let mut previous_digit = false;
+		let mut rest = text.chars().peekable();
+		while let Some(c) = rest.peek().copied() {
+			match c {
+				'0'..='9' => previous_digit = true,
+				'_' if toml && previous_digit => {
+					previous_digit = false;
+				},
+				_ => break,
+			}
+			count += 1;
+			rest.next();
+		}
+		(count > 0 && previous_digit).then(|| (count, rest.collect()))
+	};
+	let unsigned = match text.strip_prefix('-') {
+		Some(unsigned) => unsigned,
+		None if toml => text.strip_prefix('+').unwrap_or(text),
+		None => text,
+	};
+	let Some((length, mut rest)) = digits(unsigned) else {
+		return false;
+	};
+	if length > 1 && unsigned.starts_with('0') {
+		return false;
+	}
+	if let Some(fraction) = rest.strip_prefix('.') {
+		let Some((_, after)) = digits(fraction) else {
+			return false;
+		};
+		rest = after;
+	}
+	if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+		let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+		let Some((_, after)) = digits(exponent) else {
+			return false;
+		};
+		rest = after;
+	}
+	rest.is_empty()
+}
+
+/// Reads a document char by char, keeping track of the line.
+#[cfg(not(feature = "serde"))]
+struct Parser {
+	/// The chars of the document.
+	chars: Vec<char>,
+	/// The index of the next char.
+	position: usize,
+	/// The current line, from 1.
+	line: usize,
+	/// The number of arrays and tables opened around the position.
+	depth: usize,
+}
+
+#[cfg(not(feature = "serde"))]
+impl Parser {
+	/// Creates a new Parser at the start of a document.
+	fn new(text: &str) -> Self {
+		Self {
+			chars: text.chars().collect(),
+			position: 0,
+			line: 1,
+			depth: 0,
+		}
+	}
+
+	/// Returns the next char, without consuming it.
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.position).copied()
+	}
+
+	/// Returns whether the next chars are the given ones.
+	fn looking_at(&self, text: &str) -> bool {
+		text.chars().enumerate().all(|(offset, c)| self.chars.get(self.position + offset) == Some(&c))
+	}
+
+	/// Consumes the next char.
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.position += 1;
+		if c == '\n' {
+			self.line += 1;
+		}
+		Some(c)
+	}
+
+	/// Consumes the next char if it's the given one.
+	fn eat(&mut self, expected: char) -> bool {
+		if self.peek() == Some(expected) {
+			self.next();
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Consumes the next char, which must be the given one.
+	fn expect(&mut self, expected: char) -> Result<(), Error> {
+		match self.peek() {
+			Some(c) if c == expected => {
+				self.next();
+				Ok(())
+			},
+			Some(c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+			None => Err(self.error(format!("expected '{}', found the end", expected))),
+		}
+	}
+
+	/// Returns an error at the current line.
+	fn error(&self, message: String) -> Error {
+		Error::Parse { line: self.line, message }
+	}
+
+	/// Skips the spaces and the tabs.
+	fn skip_blanks(&mut self) {
+		while matches!(self.peek(), Some(' ' | '\t')) {
+			self.next();
+		}
+	}
+
+	/// Skips the whitespaces, new lines included.
+	fn skip_whitespace(&mut self) {
+		while self.peek().is_some_and(char::is_whitespace) {
+			self.next();
+		}
+	}
+
+	/// Skips the whitespaces and the TOML comments.
+	fn skip_gaps(&mut self) {
+		loop {
+			self.skip_whitespace();
+			if self.peek() != Some('#') {
+				break;
+			}
+			while self.peek().is_some_and(|c| c != '\n') {
+				self.next();
+			}
+		}
+	}
+
+	/// Consumes the end of a TOML line: blanks, a comment and the new line.
+	fn end_of_line(&mut self) -> Result<(), Error> {
+		self.skip_blanks();
+		if self.peek() == Some('#') {
+			while self.peek().is_some_and(|c| c != '\n') {
+				self.next();
+			}
+		}
+		self.eat('\r');
+		match self.peek() {
+			None => Ok(()),
+			Some('\n') => {
+				self.next();
+				Ok(())
+			},
+			Some(c) => Err(self.error(format!("unexpected '{}' at the end of the line", c))),
+		}
+	}
+
+	/// Reads the letters and digits of a keyword, e.g. true.
+	fn word(&mut self) -> String {
+		let mut word = String::new();
+		while let Some(c) = self.peek().filter(char::is_ascii_alphanumeric) {
+			word.push(c);
+			self.next();
+		}
+		word
+	}
+
+	/// Reads a string, the opening quote being consumed.
+	///
+	/// The escapes are decoded, except in the TOML literal strings,
+	/// quoted by '.
+	fn string(&mut self, quote: char) -> Result<String, Error> {
+		let mut text = String::new();
+		loop {
+			match self.next() {
+				None | Some('\n') => return Err(self.error("unterminated string".to_string())),
+				Some(c) if c == quote => return Ok(text),
+				Some('\\') if quote == '"' => text.push(self.escape()?),
+				Some(c) => text.push(c),
+			}
+		}
+	}
+
+	/// Reads an escape sequence, the backslash being consumed.
+	fn escape(&mut self) -> Result<char, Error> {
+		match self.next() {
+			Some('"') => Ok('"'),
+			Some('\\') => Ok('\\'),
+			Some('/') => Ok('/'),
+			Some('b') => Ok('\u{8}'),
+			Some('f') => Ok('\u{c}'),
+			Some('n') => Ok('\n'),
+			Some('r') => Ok('\r'),
+			Some('t') => Ok('\t'),
+			Some('u') => {
+				let code = self.hexadecimal(4)?;
+				if (0xD800..0xDC00).contains(&code) && self.looking_at("\\u") {
+					self.next();
+					self.next();
+					let low = self.hexadecimal(4)?;
+					if !(0xDC00..0xE000).contains(&low) {
+						return Err(self.error("invalid escape".to_string()));
+					}
+					let code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+					return char::from_u32(code).ok_or_else(|| self.error("invalid escape".to_string()));
+				}
+				char::from_u32(code).ok_or_else(|| self.error("invalid escape".to_string()))
+			},
+			Some('U') => {
+				let code = self.hexadecimal(8)?;
+				char::from_u32(code).ok_or_else(|| self.error("invalid escape".to_string()))
+			},
+			Some(c) => Err(self.error(format!("invalid escape '\\{}'", c))),
+			None => Err(self.error("unterminated string".to_string())),
+		}
+	}
+
+	/// Reads a number written with the given number of hexadecimal digits.
+	fn hexadecimal(&mut self, digits: usize) -> Result<u32, Error> {
+		let mut code = 0;
+		for _ in 0..digits {
+			let digit = self.next()
+				.and_then(|c| c.to_digit(16))
+				.ok_or_else(|| self.error("invalid escape".to_string()))?;
+			code = code * 16 + digit;
+		}
+		Ok(code)
+	}
+
+	/// Reads an integer or a float.
+	///
+	/// The leading zeros are rejected. In TOML, the number can start
+	/// with a + and the digits can be separated by underscores, which are
+	/// ignored.
+	fn number(&mut self, toml: bool) -> Result<Value, Error> {
+		let mut text = String::new();
+		while let Some(c) = self.peek().filter(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | '_' | 'e' | 'E')) {
+			text.push(c);
+			self.next();
+		}
+		if !valid_number(&text, toml) {
+			return Err(self.error(format!("invalid number '{}'", text)));
+		}
+		text.retain(|c| c != '_');
+		let float = text.contains(['.', 'e', 'E']);
+		let value = if float {
+			text.parse().ok().map(Value::Float)
+		} else {
+			text.parse().ok().map(Value::Integer)
+		};
+		value.ok_or_else(|| self.error(format!("invalid number '{}'", text)))
+	}
+
+	/// Reads a TOML key, made of parts separated by dots.
+	///
+	/// Each part is either bare, made of letters, digits, - and _, or quoted.
+	fn toml_key(&mut self) -> Result<Vec<String>, Error> {
+		let mut keys = Vec::new();
+		loop {
+			let key = match self.peek() {
+				Some(quote @ ('"' | '\'')) => {
+					self.next();
+					self.string(quote)?
+				},
+				_ => {
+					let mut key = String::new();
+					while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_') {
+						key.push(c);
+						self.next();
+					}
+					if key.is_empty() {
+						return Err(match self.peek() {
+							Some(c) => self.error(format!("expected a key, found '{}'", c)),
+							None => self.error("expected a key".to_string()),
+						});
+					}
+					key
+				},
+			};
+			keys.push(key);
+			if keys.len() > MAX_DEPTH {
+				return Err(self.error("too deeply nested".to_string()));
+			}
+			self.skip_blanks();
+			if !self.eat('.') {
+				return Ok(keys);
+			}
+			self.skip_blanks();
+		}
+	}
+
+	/// Reads a TOML value, which can't be nested deeper than MAX_DEPTH.
+	fn toml_value(&mut self) -> Result<Value, Error> {
+		self.nested(Self::toml_item)
+	}
+
+	/// Reads a JSON value and the whitespaces before it, which can't be
+	/// nested deeper than MAX_DEPTH.
+	fn json_value(&mut self) -> Result<Value, Error> {
+		self.nested(Self::json_item)
+	}
+
+	/// Reads a value one level deeper.
+	fn nested(&mut self, read: fn(&mut Self) -> Result<Value, Error>) -> Result<Value, Error> {
+		if self.depth >= MAX_DEPTH {
+			return Err(self.error("too deeply nested".to_string()));
+		}
+		self.depth += 1;
+		let value = read(self);
+		self.depth -= 1;
+		value
+	}
+
+	/// Reads a TOML value, at any depth.
+	fn toml_item(&mut self) -> Result<Value, Error> {
+		match self.peek() {
+			Some('"') if self.looking_at("\"\"\"") => Err(self.error("multi-line strings are not supported".to_string())),
+			Some('\'') if self.looking_at("'''") => Err(self.error("multi-line strings are not supported".to_string())),
+			Some(quote @ ('"' | '\'')) => {
+				self.next();
+				self.string(quote).map(Value::String)
+			},
+			Some('[') => {
+				self.next();
+				let mut values = Vec::new();
+				loop {
+					self.skip_gaps();
+					if self.eat(']') {
+						break;
+					}
+					values.push(self.toml_value()?);
+					self.skip_gaps();
+					if !self.eat(',') {
+						self.skip_gaps();
+						self.expect(']')?;
+						break;
+					}
+				}
+				Ok(Value::Array(values))
+			},
+			Some('{') => {
+				self.next();
+				let mut entries = Vec::new();
+				self.skip_blanks();
+				if self.eat('}') {
+					return Ok(Value::Table(entries));
+				}
+				loop {
+					self.skip_blanks();
+					let keys = self.toml_key()?;
+					self.expect('=')?;
+					self.skip_blanks();
+					let value = self.toml_value()?;
+					insert(&mut entries, &keys, value, self.line)?;
+					self.skip_blanks();
+					if !self.eat(',') {
+						self.expect('}')?;
+						return Ok(Value::Table(entries));
+					}
+				}
+			},
+			Some('t' | 'f') => match self.word().as_str() {
+				"true" => Ok(Value::Boolean(true)),
+				"false" => Ok(Value::Boolean(false)),
+				word => Err(self.error(format!("unexpected '{}'", word))),
+			},
+			Some(c) if c.is_ascii_digit() || c == '+' || c == '-' => self.number(true),
+			Some(c) => Err(self.error(format!("expected a value, found '{}'", c))),
+			None => Err(self.error("expected a value".to_string())),
+		}
+	}
+
+	/// Reads a JSON value and the whitespaces before it, at any depth.
+	fn json_item(&mut self) -> Result<Value, Error> {
+		self.skip_whitespace();
+		match self.peek() {
+			Some('"') => {
+				self.next();
+				self.string('"').map(Value::String)
+			},
+			Some('[') => {
+				self.next();
+				let mut values = Vec::new();
+				self.skip_whitespace();
+				if self.eat(']') {
+					return Ok(Value::Array(values));
+				}
+				loop {
+					values.push(self.json_value()?);
+					self.skip_whitespace();
+					if !self.eat(',') {
+						self.expect(']')?;
+						return Ok(Value::Array(values));
+					}
+				}
+			},
+			Some('{') => {
+				self.next();
+				let mut entries: Vec<(String, Value)> = Vec::new();
+				self.skip_whitespace();
+				if self.eat('}') {
+					return Ok(Value::Table(entries));
+				}
+				loop {
+					self.skip_whitespace();
+					self.expect('"')?;
+					let key = self.string('"')?;
+					self.skip_whitespace();
+					self.expect(':')?;
+					let value = self.json_value()?;
+					if entries.iter().any(|(name, _)| *name == key) {
+						return Err(self.error(format!("duplicate key '{}'", key)));
+					}
+					entries.push((key, value));
+					self.skip_whitespace();
+					if !self.eat(',') {
+						self.expect('}')?;
+						return Ok(Value::Table(entries));
+					}
+				}
+			},
+			Some('t' | 'f' | 'n') => match self.word().as_str() {
+				"true" => Ok(Value::Boolean(true)),
+				"false" => Ok(Value::Boolean(false)),
+				"null" => Err(self.error("null is not supported".to_string())),
+				word => Err(self.error(format!("unexpected '{}'", word))),
+			},
+			Some(c) if c.is_ascii_digit() || c == '-' => self.number(false),
+			Some(c) => Err(self.error(format!("expected a value, found '{}'", c))),
+			None => Err(self.error("expected a value".to_string())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::border::BorderSet;
+	use crate::style::{Color, Style};
+	use crate::theme::Theme;
+
+	/// Returns the line and the message of the error of a TOML document.
+	fn toml_error(text: &str) -> (usize, String) {
+		match Value::parse_toml(text) {
+			Err(Error::Parse { line, message }) => (line, message),
+			result => panic!("expected a parse error, got {:?}", result),
+		}
+	}
+
+	/// Returns the line and the message of the error of a JSON document.
+	fn json_error(text: &str) -> (usize, String) {
+		match Value::parse_json(text) {
+			Err(Error::Parse { line, message }) => (line, message),
+			result => panic!("expected a parse error, got {:?}", result),
+		}
+	}
+
+	/// Returns a string value.
+	fn string(text: &str) -> Value {
+		Value::String(text.to_string())
+	}
+
+	#[test]
+	fn string_escapes() {
+		let value = Value::parse_toml("a = \"t\\\"a\\\\b\\n\\t\\u00e9\\U0001F600\"\nb = 'raw\\n'").unwrap();
+		assert_eq!(value.get("a"), Some(&string("t\"a\\b\n\t\u{e9}\u{1F600}")));
+		assert_eq!(value.get("b"), Some(&string("raw\\n")));
+
+		let value = Value::parse_json(r#"["\ud83d\ude00", "\/\b\f\r"]"#).unwrap();
+		assert_eq!(value, Value::Array(vec![string("\u{1F600}"), string("/\u{8}\u{c}\r")]));
+
+		assert!(Value::parse_json(r#""\ud83d""#).is_err());
+		assert!(Value::parse_json(r#""\ud83d\u0041""#).is_err());
+		assert!(Value::parse_json(r#""\ude00""#).is_err());
+		assert!(Value::parse_json(r#""\x""#).is_err());
+		assert!(Value::parse_toml("a = \"b\n").is_err());
+	}
+
+	#[test]
+	#[cfg(not(feature = "serde"))]
+	fn escape_errors() {
+		assert_eq!(json_error(r#""\ud83d""#).1, "invalid escape");
+		assert_eq!(json_error(r#""\ud83d\u0041""#).1, "invalid escape");
+		assert_eq!(json_error(r#""\ude00""#).1, "invalid escape");
+		assert_eq!(json_error(r#""\x""#).1, "invalid escape '\\x'");
+		assert_eq!(toml_error("a = \"b\n").1, "unterminated string");
+	}
+
+	#[test]
+	fn tables() {
+		let value = Value::parse_toml("a = { b = 1, c.d = [true, 1.5] }\n[e.f]\ng = -2\n[e]\nh = 'x'").unwrap();
+		let a = value.get("a").unwrap();
+		assert_eq!(a.get("b"), Some(&Value::Integer(1)));
+		assert_eq!(a.get("c").and_then(|c| c.get("d")), Some(&Value::Array(vec![Value::Boolean(true), Value::Float(1.5)])));
+		let e = value.get("e").unwrap();
+		assert_eq!(e.get("f").and_then(|f| f.get("g")), Some(&Value::Integer(-2)));
+		assert_eq!(e.get("h"), Some(&string("x")));
+	}
+
+	#[test]
+	fn arrays_of_tables() {
+		let value = Value::parse_toml("[[a]]\nb = 1\n[a.c]\nd = 2\n[[a]]\nb = 3\n[a.c]\nd = 4").unwrap();
+		let tables = value.get("a").and_then(Value::as_array).unwrap();
+		assert_eq!(tables.len(), 2);
+		assert_eq!(tables[0].get("b"), Some(&Value::Integer(1)));
+		assert_eq!(tables[1].get("c").and_then(|c| c.get("d")), Some(&Value::Integer(4)));
+		assert_eq!(toml_error("a = 1\n[[a]]").0, 2);
+	}
+
+	#[test]
+	fn duplicates() {
+		assert_eq!(toml_error("a = 1\na = 2").0, 2);
+		assert_eq!(toml_error("a = { b = 1, b = 2 }").0, 1);
+		assert_eq!(toml_error("[a]\nb = 1\n\n[a]\nc = 2").0, 4);
+		assert_eq!(toml_error("[a.b]\n[a.b]").0, 2);
+		assert!(Value::parse_toml("[a.b]\n[a]").is_ok());
+		assert!(Value::parse_json("{\n\"a\": 1,\n\"a\": 2\n}").is_err());
+	}
+
+	#[test]
+	#[cfg(not(feature = "serde"))]
+	fn duplicate_messages() {
+		assert_eq!(toml_error("a = 1\n[[a]]").1, "'a' is not an array of tables");
+		assert_eq!(toml_error("a = 1\na = 2").1, "duplicate key 'a'");
+		assert_eq!(toml_error("a = { b = 1, b = 2 }").1, "duplicate key 'b'");
+		assert_eq!(toml_error("[a]\nb = 1\n\n[a]\nc = 2").1, "table 'a' defined twice");
+		assert_eq!(toml_error("[a.b]\n[a.b]").1, "table 'a.b' defined twice");
+		assert_eq!(json_error("{\n\"a\": 1,\n\"a\": 2\n}"), (3, "duplicate key 'a'".to_string()));
+	}
+
+	#[test]
+	fn error_lines() {
+		assert_eq!(toml_error("# comment\na = 1\n\nb = @").0, 4);
+		assert_eq!(toml_error("a = 1 b = 2").0, 1);
+		assert_eq!(json_error("{\n\n\"a\": null}").0, 3);
+		assert_eq!(json_error("[1]\n2").0, 2);
+	}
+
+	#[test]
+	#[cfg(not(feature = "serde"))]
+	fn unsupported_syntax() {
+		assert_eq!(toml_error("# comment\na = 1\n\nb = @").1, "expected a value, found '@'");
+		assert_eq!(toml_error("a = \"\"\"b\"\"\"").1, "multi-line strings are not supported");
+		assert!(Value::parse_toml("a = 1979-05-27").is_err());
+		assert!(Value::parse_toml("a = 0xff").is_err());
+		assert_eq!(json_error("{\n\n\"a\": null}").1, "null is not supported");
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn full_toml_syntax() {
+		let value = Value::parse_toml("a = \"\"\"\nb\nc\"\"\"\nd = 1979-05-27\ne = 0xff\nf = 0o17\ng = 0b101").unwrap();
+		assert_eq!(value.get("a"), Some(&string("b\nc")));
+		assert_eq!(value.get("d"), Some(&string("1979-05-27")));
+		assert_eq!(value.get("e"), Some(&Value::Integer(255)));
+		assert_eq!(value.get("f"), Some(&Value::Integer(15)));
+		assert_eq!(value.get("g"), Some(&Value::Integer(5)));
+	}
+
+	#[test]
+	fn numbers() {
+		let value = Value::parse_toml("a = 1_000\nb = +2\nc = -0.5e1_0").unwrap();
+		assert_eq!(value.get("a"), Some(&Value::Integer(1000)));
+		assert_eq!(value.get("b"), Some(&Value::Integer(2)));
+		assert_eq!(value.get("c"), Some(&Value::Float(-0.5e10)));
+		for text in ["a = 01", "a = 1__0", "a = _1", "a = 1_", "a = 1.", "a = .5", "a = 1e"] {
+			assert!(Value::parse_toml(text).is_err(), "{}", text);
+		}
+
+		let value = Value::parse_json("[0, -0.5, 1e5, 2E-1]").unwrap();
+		assert_eq!(value, Value::Array(vec![Value::Integer(0), Value::Float(-0.5), Value::Float(1e5), Value::Float(0.2)]));
+		for text in ["1_000", "01", "-01", "+1", "1.", ".5", "1e", "-"] {
+			assert!(Value::parse_json(text).is_err(), "{}", text);
+		}
+	}
+
+	#[test]
+	fn deep_nesting() {
+		let json = "[".repeat(100_000) + &"]".repeat(100_000);
+		assert!(Value::parse_json(&json).is_err());
+		let toml = "a = ".to_string() + &"[".repeat(100_000) + &"]".repeat(100_000);
+		assert!(Value::parse_toml(&toml).is_err());
+		let toml = "a = ".to_string() + &"{ b = ".repeat(100_000) + "1" + &" }".repeat(100_000);
+		assert!(Value::parse_toml(&toml).is_err());
+
+		let json = "[".repeat(50) + &"]".repeat(50);
+		assert!(Value::parse_json(&json).is_ok());
+	}
+
+	#[test]
+	#[cfg(not(feature = "serde"))]
+	fn long_keys() {
+		let toml = "a".to_string() + &".a".repeat(100_000) + " = 1";
+		assert_eq!(toml_error(&toml).1, "too deeply nested");
+		let toml = "[".to_string() + &"a.".repeat(100_000) + "a]";
+		assert_eq!(toml_error(&toml).1, "too deeply nested");
+	}
+
+	#[test]
+	fn themes_in_both_formats() {
+		let toml = Theme::from_toml("[styles]\n\"table.header\" = { foreground = \"red\", bold = true }\n[borders]\nborder = \"double\"").unwrap();
+		let json = Theme::from_json(r#"{"styles": {"table.header": {"foreground": "red", "bold": true}}, "borders": {"border": "double"}}"#).unwrap();
+		assert_eq!(toml, json);
+		assert_eq!(toml.style("table.header"), Some(Style::new().foreground(Color::Red).bold()));
+		assert_eq!(toml.border("border"), Some(BorderSet::DOUBLE));
+		assert!(Theme::from_toml("[colors]\nbase = 1").is_err());
+		assert!(Theme::from_json(r#"{"styles": {"base": {"colour": "red"}}}"#).is_err());
+	}
+
+	#[test]
+	fn unreadable_files() {
+		let path = std::env::temp_dir().join("tuim-missing-config.toml");
+		match Value::load(&path) {
+			Err(error @ Error::Io { .. }) => {
+				assert!(error.to_string().starts_with(&format!("can't read {}: ", path.display())));
+				let source = std::error::Error::source(&error).and_then(|source| source.downcast_ref::<std::io::Error>());
+				assert_eq!(source.map(std::io::Error::kind), Some(std::io::ErrorKind::NotFound));
+			},
+			result => panic!("expected an I/O error, got {:?}", result),
+		}
+	}
+}
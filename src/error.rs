@@ -0,0 +1,79 @@
+//! The error module contains the errors returned by the UI elements and
+//! by the loading of the configuration files.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error returned when a UI element is given invalid values, or when a
+/// configuration file can't be loaded.
+#[derive(Debug)]
+pub enum Error {
+	/// A line of data doesn't have as many cells as there are headers.
+	RaggedRow {
+		/// The index of the line.
+		row: usize,
+		/// The number of headers.
+		expected: usize,
+		/// The number of cells of the line.
+		found: usize,
+	},
+	/// A page after the last one has been requested.
+	PageOutOfRange {
+		/// The requested page.
+		page: u32,
+		/// The number of pages.
+		page_count: u32,
+	},
+	/// Zero items by page has been requested.
+	ZeroItemsByPage,
+	/// A configuration file isn't valid TOML or JSON.
+	Parse {
+		/// The line where the error has been found, from 1.
+		line: usize,
+		/// What is wrong.
+		message: String,
+	},
+	/// A value of a configuration file can't be used.
+	InvalidValue {
+		/// The key of the value.
+		key: String,
+		/// What is wrong.
+		message: String,
+	},
+	/// A configuration file can't be read.
+	Io {
+		/// The path of the file.
+		path: PathBuf,
+		/// Why the file can't be read.
+		error: io::Error,
+	},
+}
+
+impl fmt::Display for Error {
+	/// Describes the error.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::RaggedRow { row, expected, found } => {
+				write!(f, "line {} has {} cells, expected {}", row, found, expected)
+			},
+			Error::PageOutOfRange { page, page_count } => {
+				write!(f, "page {} is out of range, there are {} pages", page, page_count)
+			},
+			Error::ZeroItemsByPage => write!(f, "the number of items by page can't be zero"),
+			Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+			Error::InvalidValue { key, message } => write!(f, "invalid value for '{}': {}", key, message),
+			Error::Io { path, error } => write!(f, "can't read {}: {}", path.display(), error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	/// Returns the I/O error a file can't be read because of.
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Io { error, .. } => Some(error),
+			_ => None,
+		}
+	}
+}
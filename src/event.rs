@@ -0,0 +1,556 @@
+//! The event module decodes the input of the terminal into events,
+//! and delivers them to the UI elements.
+
+use crate::components::UIElement;
+
+/// An input event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+	/// A key has been pressed.
+	Key(KeyEvent),
+	/// The mouse has been used.
+	Mouse(MouseEvent),
+	/// The terminal has been resized, to the given width and height.
+	Resize(u16, u16),
+}
+
+/// A key press.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+	/// The key.
+	pub code: KeyCode,
+	/// The modifiers held with the key.
+	pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+	/// Creates a new KeyEvent.
+	pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
+		Self { code, modifiers }
+	}
+}
+
+/// A key of the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyCode {
+	/// A printable character.
+	Char(char),
+	/// Enter.
+	Enter,
+	/// Tab.
+	Tab,
+	/// Shift + Tab, as sent by most terminals.
+	BackTab,
+	/// Backspace.
+	Backspace,
+	/// Escape.
+	Esc,
+	/// Up arrow.
+	Up,
+	/// Down arrow.
+	Down,
+	/// Left arrow.
+	Left,
+	/// Right arrow.
+	Right,
+	/// Home.
+	Home,
+	/// End.
+	End,
+	/// Page up.
+	PageUp,
+	/// Page down.
+	PageDown,
+	/// Insert.
+	Insert,
+	/// Delete.
+	Delete,
+	/// A function key, from F1 to F12.
+	F(u8),
+}
+
+/// The modifiers held during a key press or a mouse event.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+	/// Whether Shift is held.
+	pub shift: bool,
+	/// Whether Alt is held.
+	pub alt: bool,
+	/// Whether Control is held.
+	pub control: bool,
+}
+
+impl Modifiers {
+	/// Returns whether no modifier is held.
+	pub fn is_empty(&self) -> bool {
+		!self.shift && !self.alt && !self.control
+	}
+
+	/// Decodes the modifiers parameter of the escape sequences,
+	/// which is 1 plus a bit field.
+	fn from_parameter(parameter: u16) -> Self {
+		let bits = parameter.saturating_sub(1);
+		Self {
+			shift: bits & 1 != 0,
+			alt: bits & 2 != 0,
+			control: bits & 4 != 0,
+		}
+	}
+}
+
+/// A mouse event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+	/// What happened.
+	pub kind: MouseKind,
+	/// The column of the mouse, from 0.
+	pub x: u16,
+	/// The line of the mouse, from 0.
+	pub y: u16,
+	/// The modifiers held.
+	pub modifiers: Modifiers,
+}
+
+/// What a mouse event is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseKind {
+	/// A button has been pressed.
+	Press(MouseButton),
+	/// A button has been released.
+	Release(MouseButton),
+	/// The mouse moved while a button is pressed.
+	Drag(MouseButton),
+	/// The mouse moved without any button pressed.
+	Move,
+	/// The wheel has been scrolled up.
+	ScrollUp,
+	/// The wheel has been scrolled down.
+	ScrollDown,
+}
+
+/// A button of the mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+	/// The left button.
+	Left,
+	/// The middle button.
+	Middle,
+	/// The right button.
+	Right,
+}
+
+/// Decodes the bytes read from the terminal into events.
+///
+/// The bytes may be split anywhere, an incomplete escape sequence is
+/// kept until the next bytes arrive.
+#[derive(Default)]
+pub struct Parser {
+	/// The bytes not decoded yet.
+	pending: Vec<u8>,
+}
+
+impl Parser {
+	/// Creates a new Parser.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns whether some bytes are waiting for the rest of a sequence.
+	pub fn has_pending(&self) -> bool {
+		!self.pending.is_empty()
+	}
+
+	/// Decodes the bytes into events.
+	///
+	/// Returns the complete events, the incomplete sequence at the end
+	/// is kept for the next call.
+	pub fn feed(&mut self, bytes: &[u8]) -> Vec<Event> {
+		self.pending.extend_from_slice(bytes);
+		let mut events = Vec::new();
+		let mut start = 0;
+		while start < self.pending.len() {
+			match decode(&self.pending[start..]) {
+				Decoded::Event(event, length) => {
+					events.extend(event);
+					start += length;
+				},
+				Decoded::Incomplete => break,
+			}
+		}
+		self.pending.drain(..start);
+		events
+	}
+
+	/// Decodes the pending bytes, considering no more bytes will come.
+	///
+	/// To be called when nothing has been read for a while: a lone
+	/// escape byte is the Escape key, not the start of a sequence.
+	pub fn flush(&mut self) -> Vec<Event> {
+		let pending = std::mem::take(&mut self.pending);
+		match pending.as_slice() {
+			[] => Vec::new(),
+			[0x1b] => vec![key(KeyCode::Esc, Modifiers::default())],
+			[0x1b, rest @ ..] => {
+				let mut events = self.feed(rest);
+				if let Some(Event::Key(first)) = events.first_mut() {
+					first.modifiers.alt = true;
+				}
+				self.pending.clear();
+				events
+			},
+			_ => {
+				self.pending.clear();
+				Vec::new()
+			},
+		}
+	}
+}
+
+/// The result of decoding the start of the input.
+enum Decoded {
+	/// An event, or nothing for unknown sequences, and the number of bytes used.
+	Event(Option<Event>, usize),
+	/// The input stops in the middle of a sequence.
+	Incomplete,
+}
+
+/// Builds a key event.
+fn key(code: KeyCode, modifiers: Modifiers) -> Event {
+	Event::Key(KeyEvent::new(code, modifiers))
+}
+
+/// Decodes the first event of the input.
+fn decode(input: &[u8]) -> Decoded {
+	let none = Modifiers::default();
+	let control = Modifiers { control: true, ..Modifiers::default() };
+	match input[0] {
+		0x1b => decode_escape(input),
+		b'\r' | b'\n' => Decoded::Event(Some(key(KeyCode::Enter, none)), 1),
+		b'\t' => Decoded::Event(Some(key(KeyCode::Tab, none)), 1),
+		0x7f | 0x08 => Decoded::Event(Some(key(KeyCode::Backspace, none)), 1),
+		0x00 => Decoded::Event(Some(key(KeyCode::Char(' '), control)), 1),
+		byte @ 0x01..=0x1a => {
+			let letter = (b'a' + byte - 1) as char;
+			Decoded::Event(Some(key(KeyCode::Char(letter), control)), 1)
+		},
+		byte @ 0x1c..=0x1f => {
+			let symbol = (b'4' + byte - 0x1c) as char;
+			Decoded::Event(Some(key(KeyCode::Char(symbol), control)), 1)
+		},
+		_ => decode_char(input),
+	}
+}
+
+/// Decodes an UTF-8 character.
+fn decode_char(input: &[u8]) -> Decoded {
+	let length = match input[0] {
+		0x00..=0x7f => 1,
+		0xc0..=0xdf => 2,
+		0xe0..=0xef => 3,
+		0xf0..=0xf7 => 4,
+		_ => return Decoded::Event(None, 1),
+	};
+	if input.len() < length {
+		return Decoded::Incomplete;
+	}
+	match std::str::from_utf8(&input[..length]).ok().and_then(|text| text.chars().next()) {
+		Some(c) => Decoded::Event(Some(key(KeyCode::Char(c), Modifiers::default())), length),
+		None => Decoded::Event(None, 1),
+	}
+}
+
+/// Decodes a sequence starting with the escape byte.
+fn decode_escape(input: &[u8]) -> Decoded {
+	match input.get(1) {
+		None => Decoded::Incomplete,
+		Some(b'[') => decode_csi(input),
+		Some(b'O') => match input.get(2) {
+			None => Decoded::Incomplete,
+			Some(final_byte) => Decoded::Event(
+				final_key(*final_byte).map(|code| key(code, Modifiers::default())),
+				3,
+			),
+		},
+		Some(0x1b) => Decoded::Event(Some(key(KeyCode::Esc, Modifiers::default())), 1),
+		Some(_) => match decode(&input[1..]) {
+			Decoded::Event(Some(Event::Key(mut event)), length) => {
+				event.modifiers.alt = true;
+				Decoded::Event(Some(Event::Key(event)), length + 1)
+			},
+			Decoded::Event(other, length) => Decoded::Event(other, length + 1),
+			Decoded::Incomplete => Decoded::Incomplete,
+		},
+	}
+}
+
+/// Returns the key of the sequences ending with a letter, e.g. ESC [ A.
+fn final_key(final_byte: u8) -> Option<KeyCode> {
+	match final_byte {
+		b'A' => Some(KeyCode::Up),
+		b'B' => Some(KeyCode::Down),
+		b'C' => Some(KeyCode::Right),
+		b'D' => Some(KeyCode::Left),
+		b'H' => Some(KeyCode::Home),
+		b'F' => Some(KeyCode::End),
+		b'P' => Some(KeyCode::F(1)),
+		b'Q' => Some(KeyCode::F(2)),
+		b'R' => Some(KeyCode::F(3)),
+		b'S' => Some(KeyCode::F(4)),
+		_ => None,
+	}
+}
+
+/// Returns the key of the sequences ending with a tilde, e.g. ESC [ 5 ~.
+fn tilde_key(number: u16) -> Option<KeyCode> {
+	match number {
+		1 | 7 => Some(KeyCode::Home),
+		2 => Some(KeyCode::Insert),
+		3 => Some(KeyCode::Delete),
+		4 | 8 => Some(KeyCode::End),
+		5 => Some(KeyCode::PageUp),
+		6 => Some(KeyCode::PageDown),
+		11..=15 => Some(KeyCode::F((number - 10) as u8)),
+		17..=21 => Some(KeyCode::F((number - 11) as u8)),
+		23 | 24 => Some(KeyCode::F((number - 12) as u8)),
+		_ => None,
+	}
+}
+
+/// Decodes a Control Sequence Introducer sequence: ESC [ parameters final.
+fn decode_csi(input: &[u8]) -> Decoded {
+	let Some(end) = input.iter().skip(2).position(|byte| (0x40..=0x7e).contains(byte)) else {
+		return Decoded::Incomplete;
+	};
+	let length = end + 3;
+	let final_byte = input[length - 1];
+	let body = &input[2..length - 1];
+	if let Some(mouse) = body.strip_prefix(b"<") {
+		return Decoded::Event(decode_mouse(mouse, final_byte), length);
+	}
+	let parameters: Vec<u16> = std::str::from_utf8(body)
+		.unwrap_or("")
+		.split(';')
+		.map(|parameter| parameter.parse().unwrap_or(0))
+		.collect();
+	let modifiers = Modifiers::from_parameter(parameters.get(1).copied().unwrap_or(1));
+	let code = match final_byte {
+		b'~' => tilde_key(parameters[0]),
+		b'Z' => Some(KeyCode::BackTab),
+		other => final_key(other),
+	};
+	Decoded::Event(code.map(|code| key(code, modifiers)), length)
+}
+
+/// Decodes the body of a SGR mouse sequence: ESC [ < button ; x ; y M.
+fn decode_mouse(body: &[u8], final_byte: u8) -> Option<Event> {
+	let parameters: Vec<u16> = std::str::from_utf8(body).ok()?
+		.split(';')
+		.map(|parameter| parameter.parse().ok())
+		.collect::<Option<Vec<u16>>>()?;
+	let [code, x, y] = parameters[..] else {
+		return None;
+	};
+	let button = match code & 0b11 {
+		0 => Some(MouseButton::Left),
+		1 => Some(MouseButton::Middle),
+		2 => Some(MouseButton::Right),
+		_ => None,
+	};
+	let kind = if code & 64 != 0 {
+		if code & 1 == 0 { MouseKind::ScrollUp } else { MouseKind::ScrollDown }
+	} else if code & 32 != 0 {
+		button.map_or(MouseKind::Move, MouseKind::Drag)
+	} else if final_byte == b'm' {
+		MouseKind::Release(button.unwrap_or(MouseButton::Left))
+	} else {
+		MouseKind::Press(button?)
+	};
+	Some(Event::Mouse(MouseEvent {
+		kind,
+		x: x.saturating_sub(1),
+		y: y.saturating_sub(1),
+		modifiers: Modifiers {
+			shift: code & 4 != 0,
+			alt: code & 8 != 0,
+			control: code & 16 != 0,
+		},
+	}))
+}
+
+/// Delivers an event to a tree of UI elements.
+///
+/// Resize events are delivered to every element. When a target is given,
+/// the event is delivered to the targeted element, then to its containers
+/// one after the other until one handles it. Otherwise, the event is
+/// delivered to every element, the children before their container,
+/// until one handles it.
+///
+/// # Parameters
+/// - root: the root of the tree.
+/// - event: the event to deliver.
+/// - target: the indexes of the children leading to the targeted element.
+///
+/// Returns whether an element handled the event.
+pub fn dispatch(root: &mut dyn UIElement, event: &Event, target: Option<&[usize]>) -> bool {
+	match (event, target) {
+		(Event::Resize(..), _) => {
+			broadcast(root, event);
+			false
+		},
+		(_, Some(path)) => bubble(root, event, path),
+		(_, None) => deliver(root, event),
+	}
+}
+
+/// Delivers an event to every element, whether they handle it or not.
+fn broadcast(element: &mut dyn UIElement, event: &Event) {
+	for child in element.children_mut() {
+		broadcast(child.as_mut(), event);
+	}
+	element.handle_event(event);
+}
+
+/// Delivers an event to the element at the end of the path, then to
+/// its containers, until one handles it.
+fn bubble(element: &mut dyn UIElement, event: &Event, path: &[usize]) -> bool {
+	if let Some((index, rest)) = path.split_first() {
+		if let Some(child) = element.children_mut().get_mut(*index) {
+			if bubble(child.as_mut(), event, rest) {
+				return true;
+			}
+		}
+	}
+	element.handle_event(event)
+}
+
+/// Delivers an event to the children, then to the element, until one handles it.
+fn deliver(element: &mut dyn UIElement, event: &Event) -> bool {
+	for child in element.children_mut() {
+		if deliver(child.as_mut(), event) {
+			return true;
+		}
+	}
+	element.handle_event(event)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Returns the modifiers given by their initials, e.g. "sc".
+	fn modifiers(held: &str) -> Modifiers {
+		Modifiers {
+			shift: held.contains('s'),
+			alt: held.contains('a'),
+			control: held.contains('c'),
+		}
+	}
+
+	/// Decodes bytes, flushing what is left.
+	fn parse(bytes: &[u8]) -> Vec<Event> {
+		let mut parser = Parser::new();
+		let mut events = parser.feed(bytes);
+		events.extend(parser.flush());
+		events
+	}
+
+	/// Builds a mouse event.
+	fn mouse(kind: MouseKind, x: u16, y: u16, held: &str) -> Event {
+		Event::Mouse(MouseEvent { kind, x, y, modifiers: modifiers(held) })
+	}
+
+	#[test]
+	fn plain_keys() {
+		assert_eq!(parse("aé東\r\t\x7f".as_bytes()), [
+			key(KeyCode::Char('a'), modifiers("")),
+			key(KeyCode::Char('é'), modifiers("")),
+			key(KeyCode::Char('東'), modifiers("")),
+			key(KeyCode::Enter, modifiers("")),
+			key(KeyCode::Tab, modifiers("")),
+			key(KeyCode::Backspace, modifiers("")),
+		]);
+		assert_eq!(parse(b"\x01\x1a\x00"), [
+			key(KeyCode::Char('a'), modifiers("c")),
+			key(KeyCode::Char('z'), modifiers("c")),
+			key(KeyCode::Char(' '), modifiers("c")),
+		]);
+		assert_eq!(parse(b"\x1bx"), [key(KeyCode::Char('x'), modifiers("a"))]);
+	}
+
+	#[test]
+	fn csi_keys_and_modifiers() {
+		assert_eq!(parse(b"\x1b[A\x1b[1;5A\x1b[1;2C\x1b[1;8D"), [
+			key(KeyCode::Up, modifiers("")),
+			key(KeyCode::Up, modifiers("c")),
+			key(KeyCode::Right, modifiers("s")),
+			key(KeyCode::Left, modifiers("sac")),
+		]);
+		assert_eq!(parse(b"\x1b[3~\x1b[3;3~\x1b[5~\x1b[15;5~\x1b[24~\x1b[Z\x1bOP\x1b[1;2Q"), [
+			key(KeyCode::Delete, modifiers("")),
+			key(KeyCode::Delete, modifiers("a")),
+			key(KeyCode::PageUp, modifiers("")),
+			key(KeyCode::F(5), modifiers("c")),
+			key(KeyCode::F(12), modifiers("")),
+			key(KeyCode::BackTab, modifiers("")),
+			key(KeyCode::F(1), modifiers("")),
+			key(KeyCode::F(2), modifiers("s")),
+		]);
+		// Unknown sequences are skipped.
+		assert_eq!(parse(b"\x1b[99~\x1b[1;5Xa"), [key(KeyCode::Char('a'), modifiers(""))]);
+	}
+
+	#[test]
+	fn sgr_mouse() {
+		assert_eq!(parse(b"\x1b[<0;10;5M\x1b[<0;10;5m\x1b[<2;1;1M\x1b[<1;3;3m"), [
+			mouse(MouseKind::Press(MouseButton::Left), 9, 4, ""),
+			mouse(MouseKind::Release(MouseButton::Left), 9, 4, ""),
+			mouse(MouseKind::Press(MouseButton::Right), 0, 0, ""),
+			mouse(MouseKind::Release(MouseButton::Middle), 2, 2, ""),
+		]);
+		assert_eq!(parse(b"\x1b[<64;1;2M\x1b[<65;1;2M\x1b[<32;3;4M\x1b[<35;3;4M"), [
+			mouse(MouseKind::ScrollUp, 0, 1, ""),
+			mouse(MouseKind::ScrollDown, 0, 1, ""),
+			mouse(MouseKind::Drag(MouseButton::Left), 2, 3, ""),
+			mouse(MouseKind::Move, 2, 3, ""),
+		]);
+		assert_eq!(parse(b"\x1b[<20;200;100M\x1b[<8;1;1M"), [
+			mouse(MouseKind::Press(MouseButton::Left), 199, 99, "sc"),
+			mouse(MouseKind::Press(MouseButton::Left), 0, 0, "a"),
+		]);
+		// Malformed reports are skipped.
+		assert_eq!(parse(b"\x1b[<0;1M\x1b[<0;1;1;1M\x1b[<0;99999;1M"), []);
+	}
+
+	#[test]
+	fn sequences_split_across_reads() {
+		let mut parser = Parser::new();
+		assert_eq!(parser.feed(b"a\x1b[1;"), [key(KeyCode::Char('a'), modifiers(""))]);
+		assert!(parser.has_pending());
+		assert_eq!(parser.feed(b"5"), []);
+		assert_eq!(parser.feed(b"Ab"), [key(KeyCode::Up, modifiers("c")), key(KeyCode::Char('b'), modifiers(""))]);
+		assert!(!parser.has_pending());
+
+		assert_eq!(parser.feed(b"\x1b[<0;1"), []);
+		assert_eq!(parser.feed(b"2;3M"), [mouse(MouseKind::Press(MouseButton::Left), 11, 2, "")]);
+
+		let bytes = "東".as_bytes();
+		assert_eq!(parser.feed(&bytes[..1]), []);
+		assert_eq!(parser.feed(&bytes[1..2]), []);
+		assert_eq!(parser.feed(&bytes[2..]), [key(KeyCode::Char('東'), modifiers(""))]);
+	}
+
+	#[test]
+	fn lone_escape_is_flushed() {
+		let mut parser = Parser::new();
+		assert_eq!(parser.feed(b"\x1b"), []);
+		assert!(parser.has_pending());
+		assert_eq!(parser.flush(), [key(KeyCode::Esc, modifiers(""))]);
+		assert!(!parser.has_pending());
+		assert_eq!(parser.flush(), []);
+
+		// Two escapes in a row are two presses of the key.
+		assert_eq!(parse(b"\x1b\x1b"), [key(KeyCode::Esc, modifiers("")), key(KeyCode::Esc, modifiers(""))]);
+		// An escape followed by an unfinished sequence is Alt with the key.
+		assert_eq!(parse(b"\x1b["), [key(KeyCode::Char('['), modifiers("a"))]);
+	}
+}
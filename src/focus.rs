@@ -0,0 +1,317 @@
+//! The focus module tracks which UI element receives the keys.
+
+use crate::components::{self, Layout, UIElement};
+use crate::event::{self, Event, KeyCode};
+
+/// A direction to move the focus to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+	/// Towards the top.
+	Up,
+	/// Towards the bottom.
+	Down,
+	/// Towards the left.
+	Left,
+	/// Towards the right.
+	Right,
+}
+
+/// Tracks the focused element of a tree of UI elements.
+///
+/// Elements are identified by the indexes of the children leading to
+/// them from the root. Only the elements returning true in `focusable`
+/// can be focused, and the elements of inactive tabs are skipped.
+#[derive(Default)]
+pub struct Focus {
+	/// The focused element, if any.
+	focused: Option<Vec<usize>>,
+}
+
+impl Focus {
+	/// Creates a new Focus, without any focused element.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the focused element.
+	pub fn focused(&self) -> Option<&[usize]> {
+		self.focused.as_deref()
+	}
+
+	/// Focuses an element.
+	///
+	/// Returns false, and keeps the focus unchanged, when the element
+	/// doesn't exist or is not focusable.
+	pub fn focus(&mut self, root: &mut dyn UIElement, path: &[usize]) -> bool {
+		match components::element_at_path(root, path) {
+			Some(element) if element.focusable() => {},
+			_ => return false,
+		}
+		if let Some(previous) = self.focused.take() {
+			if let Some(element) = components::element_at_path_mut(root, &previous) {
+				element.set_focused(false);
+			}
+		}
+		if let Some(element) = components::element_at_path_mut(root, path) {
+			element.set_focused(true);
+		}
+		self.focused = Some(path.to_vec());
+		true
+	}
+
+	/// Focuses the element at the given path, or its closest focusable
+	/// container, e.g. after a click.
+	///
+	/// Returns whether an element has been focused.
+	pub fn focus_closest(&mut self, root: &mut dyn UIElement, path: &[usize]) -> bool {
+		(0..=path.len()).rev().any(|length| self.focus(root, &path[..length]))
+	}
+
+	/// Focuses the next focusable element, in the order of the tree.
+	///
+	/// After the last element, the first one is focused.
+	pub fn next(&mut self, root: &mut dyn UIElement) -> bool {
+		let focusables = focusables(root);
+		let index = self.position_in(&focusables).map_or(0, |index| index + 1);
+		match focusables.get(index).or(focusables.first()) {
+			Some(path) => self.focus(root, &path.clone()),
+			None => false,
+		}
+	}
+
+	/// Focuses the previous focusable element, in the order of the tree.
+	///
+	/// Before the first element, the last one is focused.
+	pub fn previous(&mut self, root: &mut dyn UIElement) -> bool {
+		let focusables = focusables(root);
+		let index = self.position_in(&focusables)
+			.and_then(|index| index.checked_sub(1));
+		match index.and_then(|index| focusables.get(index)).or(focusables.last()) {
+			Some(path) => self.focus(root, &path.clone()),
+			None => false,
+		}
+	}
+
+	/// Moves the focus in a direction.
+	///
+	/// The closest container laid out in that direction (horizontally for
+	/// left and right, vertically for up and down) is looked for, and the
+	/// first focusable element among the next elements of that container
+	/// is focused.
+	///
+	/// Returns whether the focus moved.
+	pub fn move_towards(&mut self, root: &mut dyn UIElement, direction: Direction) -> bool {
+		let Some(current) = self.focused.clone() else {
+			return self.next(root);
+		};
+		let forward = matches!(direction, Direction::Down | Direction::Right);
+		for depth in (0..current.len()).rev() {
+			let (ancestor_path, index) = (&current[..depth], current[depth]);
+			let Some(ancestor) = components::element_at_path(root, ancestor_path) else {
+				continue;
+			};
+			let along = matches!(
+				(ancestor.layout(), direction),
+				(Some(Layout::Horizontal), Direction::Left | Direction::Right)
+					| (Some(Layout::Vertical), Direction::Up | Direction::Down)
+			);
+			if !along {
+				continue;
+			}
+			let siblings: Vec<usize> = if forward {
+				(index + 1..ancestor.children().len()).collect()
+			} else {
+				(0..index).rev().collect()
+			};
+			for sibling in siblings {
+				let child = &ancestor.children()[sibling];
+				if !ancestor.child_visible(sibling) {
+					continue;
+				}
+				let found = focusables(child.as_ref());
+				let target = if forward { found.first() } else { found.last() };
+				if let Some(target) = target {
+					let mut path = ancestor_path.to_vec();
+					path.push(sibling);
+					path.extend_from_slice(target);
+					return self.focus(root, &path);
+				}
+			}
+		}
+		false
+	}
+
+	/// Delivers an event to the focused element.
+	///
+	/// Keys are delivered to the focused element first, then to its
+	/// containers, until one handles it. When no element handles it,
+	/// Tab and Shift+Tab move to the next and previous element, and the
+	/// arrows move the focus in their direction. Other events are
+	/// delivered as with `event::dispatch`.
+	///
+	/// Returns whether the event has been handled.
+	pub fn dispatch(&mut self, root: &mut dyn UIElement, event: &Event) -> bool {
+		let Event::Key(key) = event else {
+			return event::dispatch(root, event, None);
+		};
+		if let Some(focused) = self.focused.clone() {
+			if event::dispatch(root, event, Some(&focused)) {
+				return true;
+			}
+		}
+		if !key.modifiers.is_empty() {
+			return false;
+		}
+		match key.code {
+			KeyCode::Tab => self.next(root),
+			KeyCode::BackTab => self.previous(root),
+			KeyCode::Up => self.move_towards(root, Direction::Up),
+			KeyCode::Down => self.move_towards(root, Direction::Down),
+			KeyCode::Left => self.move_towards(root, Direction::Left),
+			KeyCode::Right => self.move_towards(root, Direction::Right),
+			_ => false,
+		}
+	}
+
+	/// Returns the position of the focused element in a list of elements.
+	fn position_in(&self, paths: &[Vec<usize>]) -> Option<usize> {
+		let focused = self.focused.as_ref()?;
+		paths.iter().position(|path| path == focused)
+	}
+}
+
+/// Returns the focusable elements of a tree, in the order of the tree.
+fn focusables(root: &dyn UIElement) -> Vec<Vec<usize>> {
+	fn walk(element: &dyn UIElement, path: &mut Vec<usize>, found: &mut Vec<Vec<usize>>) {
+		if element.focusable() {
+			found.push(path.clone());
+		}
+		for (index, child) in element.children().iter().enumerate() {
+			if element.child_visible(index) {
+				path.push(index);
+				walk(child.as_ref(), path, found);
+				path.pop();
+			}
+		}
+	}
+	let mut found = Vec::new();
+	walk(root, &mut Vec::new(), &mut found);
+	found
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::components::table::{Table, TableTrait};
+	use crate::components::Container;
+	use crate::event::{KeyEvent, Modifiers};
+
+	/// Returns an empty table, which is focusable and leaves the keys
+	/// to the focus.
+	fn table() -> Box<dyn UIElement> {
+		Box::new(Table::new(&["Name".to_string()], Vec::new()))
+	}
+
+	/// Returns a container, which is not focusable.
+	fn container(elements: Vec<Box<dyn UIElement>>, layout: Layout) -> Box<dyn UIElement> {
+		Box::new(Container::new(elements, layout))
+	}
+
+	/// Returns a key press without modifiers.
+	fn key(code: KeyCode) -> Event {
+		Event::Key(KeyEvent::new(code, Modifiers::default()))
+	}
+
+	#[test]
+	fn tab_cycles_through_the_tree() {
+		let mut root = Container::new(vec![
+			table(),
+			container(vec![table(), container(Vec::new(), Layout::Vertical), table()], Layout::Horizontal),
+		], Layout::Vertical);
+		let mut focus = Focus::new();
+
+		for expected in [&[0][..], &[1, 0], &[1, 2], &[0]] {
+			assert!(focus.dispatch(&mut root, &key(KeyCode::Tab)));
+			assert_eq!(focus.focused(), Some(expected));
+		}
+		assert!(root.children()[0].focused());
+		assert!(!root.children()[1].children()[2].focused());
+
+		for expected in [&[1, 2][..], &[1, 0], &[0]] {
+			assert!(focus.dispatch(&mut root, &key(KeyCode::BackTab)));
+			assert_eq!(focus.focused(), Some(expected));
+		}
+		assert!(!focus.dispatch(&mut root, &Event::Key(KeyEvent::new(KeyCode::Tab, Modifiers { control: true, ..Modifiers::default() }))));
+		assert_eq!(focus.focused(), Some(&[0][..]));
+	}
+
+	#[test]
+	fn arrows_move_between_siblings() {
+		let mut root = Container::new(vec![
+			table(),
+			container(vec![table(), table()], Layout::Vertical),
+			table(),
+		], Layout::Horizontal);
+		let mut focus = Focus::new();
+		assert!(focus.dispatch(&mut root, &key(KeyCode::Right)));
+		assert_eq!(focus.focused(), Some(&[0][..]));
+
+		// Down has no vertical container to move in.
+		assert!(!focus.dispatch(&mut root, &key(KeyCode::Down)));
+		assert_eq!(focus.focused(), Some(&[0][..]));
+
+		let moves = [
+			(KeyCode::Right, &[1, 0][..]),
+			(KeyCode::Down, &[1, 1]),
+			(KeyCode::Right, &[2]),
+			(KeyCode::Left, &[1, 1]),
+			(KeyCode::Up, &[1, 0]),
+			(KeyCode::Left, &[0]),
+		];
+		for (code, expected) in moves {
+			assert!(focus.dispatch(&mut root, &key(code)), "{:?}", code);
+			assert_eq!(focus.focused(), Some(expected));
+		}
+		assert!(!focus.dispatch(&mut root, &key(KeyCode::Left)));
+		assert!(!focus.dispatch(&mut root, &key(KeyCode::Up)));
+		assert_eq!(focus.focused(), Some(&[0][..]));
+	}
+
+	#[test]
+	fn unfocusable_elements_are_skipped() {
+		let mut tabs = Container::new(vec![table(), table()], Layout::Tabbed);
+		tabs.set_active_tab(1);
+		let mut root = Container::new(vec![
+			container(vec![container(Vec::new(), Layout::Vertical)], Layout::Vertical),
+			Box::new(tabs),
+			table(),
+		], Layout::Vertical);
+		let mut focus = Focus::new();
+
+		assert!(focus.next(&mut root));
+		assert_eq!(focus.focused(), Some(&[1, 1][..]));
+		assert!(focus.next(&mut root));
+		assert_eq!(focus.focused(), Some(&[2][..]));
+		assert!(focus.move_towards(&mut root, Direction::Up));
+		assert_eq!(focus.focused(), Some(&[1, 1][..]));
+
+		assert!(!focus.focus(&mut root, &[0]));
+		assert!(!focus.focus(&mut root, &[5]));
+		assert_eq!(focus.focused(), Some(&[1, 1][..]));
+		assert!(focus.focus_closest(&mut root, &[2, 3]));
+		assert_eq!(focus.focused(), Some(&[2][..]));
+		assert!(!focus.focus_closest(&mut root, &[0, 0]));
+	}
+
+	#[test]
+	fn empty_tree() {
+		let mut root = Container::new(Vec::new(), Layout::Vertical);
+		let mut focus = Focus::new();
+		assert!(!focus.next(&mut root));
+		assert!(!focus.previous(&mut root));
+		for code in [KeyCode::Tab, KeyCode::BackTab, KeyCode::Down, KeyCode::Right] {
+			assert!(!focus.dispatch(&mut root, &key(code)));
+		}
+		assert_eq!(focus.focused(), None);
+	}
+}
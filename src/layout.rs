@@ -0,0 +1,487 @@
+//! The layout module computes where the UI elements are placed on screen.
+
+use crate::components::{Layout, Position, Size, UIElement};
+
+/// A rectangle on the screen, in chars.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+	/// The column of the top left corner.
+	pub x: u16,
+	/// The line of the top left corner.
+	pub y: u16,
+	/// The width, in chars.
+	pub width: u16,
+	/// The height, in chars.
+	pub height: u16,
+}
+
+impl Rect {
+	/// Creates a new Rect.
+	pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+		Self { x, y, width, height }
+	}
+
+	/// Returns whether the rectangle covers no char at all.
+	pub fn is_empty(&self) -> bool {
+		self.width == 0 || self.height == 0
+	}
+
+	/// Returns the column right after the rectangle.
+	pub fn right(&self) -> u16 {
+		self.x.saturating_add(self.width)
+	}
+
+	/// Returns the line right after the rectangle.
+	pub fn bottom(&self) -> u16 {
+		self.y.saturating_add(self.height)
+	}
+
+	/// Returns whether the two rectangles share at least one char.
+	pub fn intersects(&self, other: &Rect) -> bool {
+		self.x < other.right() && other.x < self.right()
+			&& self.y < other.bottom() && other.y < self.bottom()
+	}
+
+	/// Returns whether a position is inside the rectangle.
+	pub fn contains(&self, x: u16, y: u16) -> bool {
+		x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+	}
+
+	/// Returns the part of the rectangle which is also in the other one.
+	///
+	/// When they don't intersect, an empty rectangle is returned.
+	pub fn intersection(&self, other: &Rect) -> Rect {
+		let x = self.x.max(other.x);
+		let y = self.y.max(other.y);
+		let right = self.right().min(other.right());
+		let bottom = self.bottom().min(other.bottom());
+		if x < right && y < bottom {
+			Rect::new(x, y, right - x, bottom - y)
+		} else {
+			Rect::new(x.min(right), y.min(bottom), 0, 0)
+		}
+	}
+
+	/// Returns the rectangle shrunk by the given space on each side.
+	///
+	/// When the sides take the whole width or height, an empty rectangle
+	/// is returned.
+	pub fn shrink(&self, sides: Sides<u16>) -> Rect {
+		let horizontal = sides.left.saturating_add(sides.right);
+		let vertical = sides.top.saturating_add(sides.bottom);
+		if horizontal >= self.width || vertical >= self.height {
+			let x = self.x.saturating_add(sides.left).min(self.right());
+			let y = self.y.saturating_add(sides.top).min(self.bottom());
+			return Rect::new(x, y, 0, 0);
+		}
+		Rect::new(self.x + sides.left, self.y + sides.top, self.width - horizontal, self.height - vertical)
+	}
+}
+
+/// Where a text is placed in the space it's given, e.g. a title or the
+/// cells of a column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+	/// Against the left side.
+	#[default]
+	Left,
+	/// In the middle, closer to the left side when it can't be exactly.
+	Center,
+	/// Against the right side.
+	Right,
+}
+
+impl Align {
+	/// Returns the space left before a text of a given length, to align it.
+	pub fn indent(&self, length: u16, width: u16) -> u16 {
+		let space = width.saturating_sub(length);
+		match self {
+			Align::Left => 0,
+			Align::Center => space / 2,
+			Align::Right => space,
+		}
+	}
+}
+
+/// A value for each side of a rectangle, e.g. the padding of an element.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sides<T> {
+	/// The value of the top side.
+	pub top: T,
+	/// The value of the right side.
+	pub right: T,
+	/// The value of the bottom side.
+	pub bottom: T,
+	/// The value of the left side.
+	pub left: T,
+}
+
+impl<T: Copy> Sides<T> {
+	/// Creates new Sides, clockwise from the top.
+	pub fn new(top: T, right: T, bottom: T, left: T) -> Self {
+		Self { top, right, bottom, left }
+	}
+
+	/// Creates new Sides with the same value on every side.
+	pub fn all(value: T) -> Self {
+		Self::new(value, value, value, value)
+	}
+
+	/// Creates new Sides with a value for the top and the bottom, and
+	/// another one for the left and the right.
+	pub fn symmetric(vertical: T, horizontal: T) -> Self {
+		Self::new(vertical, horizontal, vertical, horizontal)
+	}
+}
+
+impl Size {
+	/// Resolves the size against the available space.
+	///
+	/// Returns None for Size::Auto, since it depends on the siblings.
+	/// The result never exceeds the available space.
+	pub fn resolve(&self, available: u16) -> Option<u16> {
+		match self {
+			Size::Auto => None,
+			Size::Chars(chars) => Some((*chars as u16).min(available)),
+			Size::Percents(percents) => {
+				let size = available as u32 * (*percents).min(100) as u32 / 100;
+				Some(size as u16)
+			},
+		}
+	}
+}
+
+/// Computes the final area of an element, from its position and offset.
+///
+/// An element with Position::Absolute is placed from the top left corner
+/// of the screen and sized against the screen, Size::Auto taking the rest
+/// of the screen. An element with Position::Centered is sized the same way
+/// and centred on the screen. An element with Position::Relative is moved by its
+/// offset from the area given by its container, and clipped to it.
+///
+/// The margin of the element is then left around it.
+///
+/// # Parameters
+/// - element: the element to place.
+/// - flow: the area given to the element by its container.
+/// - parent: the area inside the container.
+/// - screen: the area of the whole screen.
+pub fn position(element: &dyn UIElement, flow: Rect, parent: Rect, screen: Rect) -> Rect {
+	let (x, y) = element.offset();
+	let area = match element.position() {
+		Position::Absolute => {
+			let x = screen.x.saturating_add(x).min(screen.right());
+			let y = screen.y.saturating_add(y).min(screen.bottom());
+			let (available_width, available_height) = (screen.right() - x, screen.bottom() - y);
+			let width = element.width().resolve(screen.width).unwrap_or(available_width);
+			let height = element.height().resolve(screen.height).unwrap_or(available_height);
+			Rect::new(x, y, width.min(available_width), height.min(available_height))
+		},
+		Position::Centered => {
+			let width = element.width().resolve(screen.width).unwrap_or(screen.width);
+			let height = element.height().resolve(screen.height).unwrap_or(screen.height);
+			Rect::new(
+				screen.x + (screen.width - width) / 2,
+				screen.y + (screen.height - height) / 2,
+				width,
+				height,
+			)
+		},
+		Position::Relative => {
+			if x == 0 && y == 0 {
+				flow
+			} else {
+				Rect::new(flow.x.saturating_add(x), flow.y.saturating_add(y), flow.width, flow.height)
+					.intersection(&parent)
+			}
+		},
+	};
+	area.shrink(element.margin())
+}
+
+/// Splits an area between elements, according to a layout.
+///
+/// The elements are placed one after the other along the layout
+/// direction, with the lengths computed by `solve`. Across the layout
+/// direction, the elements take their own size, or all the available
+/// space for Size::Auto. With Layout::Tabbed, every element gets the
+/// whole area.
+///
+/// # Parameters
+/// - area: the area to split.
+/// - layout: the layout used to place the elements.
+/// - sizes: the width and the height of each element.
+pub fn split(area: Rect, layout: &Layout, sizes: &[(Size, Size)]) -> Vec<Rect> {
+	let horizontal = match layout {
+		Layout::Horizontal => true,
+		Layout::Vertical => false,
+		Layout::Tabbed => return sizes.iter().map(|_| area).collect(),
+	};
+	let (main, cross) = if horizontal {
+		(area.width, area.height)
+	} else {
+		(area.height, area.width)
+	};
+	let lengths: Vec<Size> = sizes.iter()
+		.map(|(width, height)| if horizontal { width.clone() } else { height.clone() })
+		.collect();
+
+	let mut offset = 0u16;
+	let mut areas = Vec::with_capacity(sizes.len());
+	for ((width, height), length) in sizes.iter().zip(solve(main, &lengths)) {
+		let thickness = if horizontal { height } else { width }
+			.resolve(cross)
+			.unwrap_or(cross);
+		areas.push(if horizontal {
+			Rect::new(area.x + offset, area.y, length, thickness)
+		} else {
+			Rect::new(area.x, area.y + offset, thickness, length)
+		});
+		offset += length;
+	}
+	areas
+}
+
+/// Shares a length between elements.
+///
+/// The elements with a fixed size are served first, in order. When they
+/// need more than the available space, they are cut and the following
+/// ones get nothing. Percentages are rounded so that they add up to the
+/// rounded total, the chars lost by rounding go to the elements losing
+/// the most. Finally, the remaining space is shared between the
+/// Size::Auto elements, the first ones getting the leftover chars.
+///
+/// # Parameters
+/// - available: the length to share.
+/// - sizes: the size of each element along the length.
+///
+/// # Examples
+/// ```
+/// use tuim::components::Size;
+/// use tuim::layout::solve;
+///
+/// // Size::Auto elements share what the others left.
+/// assert_eq!(solve(10, &[Size::Chars(3), Size::Auto, Size::Auto]), [3, 4, 3]);
+/// assert_eq!(solve(10, &[Size::Auto, Size::Chars(4)]), [6, 4]);
+///
+/// // Rounded percentages still fill the expected space.
+/// assert_eq!(solve(10, &[Size::Percents(33), Size::Percents(33), Size::Percents(34)]), [3, 3, 4]);
+/// assert_eq!(solve(11, &[Size::Percents(50), Size::Percents(50)]), [6, 5]);
+/// assert_eq!(solve(9, &[Size::Percents(150)]), [9]);
+///
+/// // Overflowing elements are cut, in order.
+/// assert_eq!(solve(10, &[Size::Chars(6), Size::Chars(6), Size::Auto]), [6, 4, 0]);
+/// assert_eq!(solve(10, &[Size::Percents(80), Size::Percents(80)]), [8, 2]);
+///
+/// // Nothing to share.
+/// assert_eq!(solve(0, &[Size::Chars(2), Size::Auto]), [0, 0]);
+/// assert!(solve(5, &[]).is_empty());
+/// ```
+pub fn solve(available: u16, sizes: &[Size]) -> Vec<u16> {
+	let mut wanted: Vec<Option<u32>> = sizes.iter()
+		.map(|size| match size {
+			Size::Auto => None,
+			Size::Chars(chars) => Some(*chars as u32),
+			Size::Percents(percents) => Some(available as u32 * (*percents).min(100) as u32 / 100),
+		})
+		.collect();
+
+	// Give the chars lost by rounding down the percentages back,
+	// largest remainders first.
+	let percents: Vec<(usize, u32)> = sizes.iter()
+		.enumerate()
+		.filter_map(|(index, size)| match size {
+			Size::Percents(percents) => Some((index, available as u32 * (*percents).min(100) as u32)),
+			_ => None,
+		})
+		.collect();
+	let exact: u32 = percents.iter().map(|(_, hundredths)| hundredths).sum();
+	let floored: u32 = percents.iter().map(|(_, hundredths)| hundredths / 100).sum();
+	let mut lost = ((exact + 50) / 100).saturating_sub(floored);
+	let mut by_remainder = percents.clone();
+	by_remainder.sort_by_key(|(_, hundredths)| std::cmp::Reverse(hundredths % 100));
+	for (index, hundredths) in by_remainder {
+		if lost == 0 || hundredths % 100 == 0 {
+			break;
+		}
+		if let Some(length) = wanted[index].as_mut() {
+			*length += 1;
+			lost -= 1;
+		}
+	}
+
+	let mut remaining = available as u32;
+	let mut lengths: Vec<u16> = wanted.iter_mut()
+		.map(|length| match length {
+			Some(length) => {
+				let length = (*length).min(remaining);
+				remaining -= length;
+				length as u16
+			},
+			None => 0,
+		})
+		.collect();
+
+	let autos = wanted.iter().filter(|length| length.is_none()).count() as u32;
+	if let Some(share) = remaining.checked_div(autos) {
+		let leftover = remaining % autos;
+		let mut auto_index = 0;
+		for (length, size) in lengths.iter_mut().zip(&wanted) {
+			if size.is_none() {
+				*length = (share + u32::from(auto_index < leftover)) as u16;
+				auto_index += 1;
+			}
+		}
+	}
+	lengths
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::components::Container;
+
+	/// The whole screen of the tests.
+	const SCREEN: Rect = Rect { x: 0, y: 0, width: 20, height: 10 };
+
+	/// Returns an empty element positioned as given.
+	fn element(position: Position, offset: (u16, u16), width: Size, height: Size) -> Container {
+		let mut element = Container::new(Vec::new(), Layout::Vertical);
+		element.set_position(position);
+		element.set_offset(offset.0, offset.1);
+		element.set_width(width);
+		element.set_height(height);
+		element
+	}
+
+	#[test]
+	fn split_serves_the_fixed_sizes_first() {
+		let area = Rect::new(0, 0, 10, 4);
+		let sizes = [
+			(Size::Chars(3), Size::Auto),
+			(Size::Auto, Size::Chars(2)),
+			(Size::Percents(20), Size::Auto),
+		];
+		let areas = split(area, &Layout::Horizontal, &sizes);
+		assert_eq!(areas, [Rect::new(0, 0, 3, 4), Rect::new(3, 0, 5, 2), Rect::new(8, 0, 2, 4)]);
+	}
+
+	#[test]
+	fn intersection() {
+		let area = Rect::new(0, 0, 10, 10);
+		assert_eq!(area.intersection(&Rect::new(5, 5, 10, 10)), Rect::new(5, 5, 5, 5));
+		assert_eq!(area.intersection(&Rect::new(2, 3, 4, 5)), Rect::new(2, 3, 4, 5));
+		assert!(area.intersection(&Rect::new(10, 0, 5, 5)).is_empty());
+		assert!(area.intersection(&Rect::new(20, 20, 2, 2)).is_empty());
+		assert!(!area.intersects(&Rect::new(10, 0, 5, 5)));
+		assert!(area.intersects(&Rect::new(9, 9, 5, 5)));
+		assert!(area.contains(0, 9));
+		assert!(!area.contains(10, 0));
+		assert!(!Rect::new(3, 3, 0, 0).contains(3, 3));
+	}
+
+	#[test]
+	fn align_indent() {
+		assert_eq!(Align::Left.indent(3, 10), 0);
+		assert_eq!(Align::Center.indent(3, 10), 3);
+		assert_eq!(Align::Right.indent(3, 10), 7);
+		assert_eq!(Align::Right.indent(12, 10), 0);
+	}
+
+	#[test]
+	fn shrink() {
+		let area = Rect::new(10, 10, 20, 10);
+		assert_eq!(area.shrink(Sides::new(1, 2, 3, 4)), Rect::new(14, 11, 14, 6));
+		assert_eq!(area.shrink(Sides::default()), area);
+		assert_eq!(area.shrink(Sides::symmetric(0, 10)), Rect::new(20, 10, 0, 0));
+		assert_eq!(area.shrink(Sides::all(50)), Rect::new(30, 20, 0, 0));
+	}
+
+	#[test]
+	fn solve_edge_cases() {
+		assert_eq!(solve(7, &[Size::Percents(50), Size::Auto, Size::Auto]), [4, 2, 1]);
+		assert_eq!(solve(3, &[Size::Percents(0), Size::Auto]), [0, 3]);
+		assert_eq!(solve(3, &[Size::Auto, Size::Auto, Size::Auto, Size::Auto]), [1, 1, 1, 0]);
+		assert_eq!(solve(u16::MAX, &[Size::Chars(255), Size::Percents(100)]), [255, u16::MAX - 255]);
+	}
+
+	#[test]
+	fn split_horizontal() {
+		let areas = split(Rect::new(0, 0, 10, 4), &Layout::Horizontal, &[
+			(Size::Chars(3), Size::Auto),
+			(Size::Auto, Size::Chars(2)),
+		]);
+		assert_eq!(areas, [Rect::new(0, 0, 3, 4), Rect::new(3, 0, 7, 2)]);
+	}
+
+	#[test]
+	fn split_vertical() {
+		let areas = split(Rect::new(2, 1, 6, 10), &Layout::Vertical, &[
+			(Size::Auto, Size::Percents(50)),
+			(Size::Chars(4), Size::Auto),
+		]);
+		assert_eq!(areas, [Rect::new(2, 1, 6, 5), Rect::new(2, 6, 4, 5)]);
+	}
+
+	#[test]
+	fn split_overflow() {
+		let areas = split(Rect::new(0, 0, 5, 3), &Layout::Horizontal, &[
+			(Size::Chars(4), Size::Chars(20)),
+			(Size::Chars(4), Size::Auto),
+			(Size::Auto, Size::Auto),
+		]);
+		assert_eq!(areas, [Rect::new(0, 0, 4, 3), Rect::new(4, 0, 1, 3), Rect::new(5, 0, 0, 3)]);
+	}
+
+	#[test]
+	fn split_tabbed_and_empty() {
+		let area = Rect::new(1, 1, 8, 4);
+		let areas = split(area, &Layout::Tabbed, &[(Size::Chars(2), Size::Auto), (Size::Auto, Size::Auto)]);
+		assert_eq!(areas, [area, area]);
+		assert!(split(area, &Layout::Horizontal, &[]).is_empty());
+	}
+
+	#[test]
+	fn position_relative() {
+		let flow = Rect::new(5, 5, 6, 3);
+		let parent = Rect::new(0, 0, 12, 8);
+		let still = element(Position::Relative, (0, 0), Size::Auto, Size::Auto);
+		assert_eq!(position(&still, flow, parent, SCREEN), flow);
+		let moved = element(Position::Relative, (2, 1), Size::Auto, Size::Auto);
+		assert_eq!(position(&moved, flow, parent, SCREEN), Rect::new(7, 6, 5, 2));
+		let outside = element(Position::Relative, (50, 0), Size::Auto, Size::Auto);
+		assert!(position(&outside, flow, parent, SCREEN).is_empty());
+	}
+
+	#[test]
+	fn position_absolute() {
+		let flow = Rect::new(1, 1, 2, 2);
+		let placed = element(Position::Absolute, (3, 2), Size::Chars(5), Size::Auto);
+		assert_eq!(position(&placed, flow, flow, SCREEN), Rect::new(3, 2, 5, 8));
+		let clipped = element(Position::Absolute, (15, 0), Size::Chars(10), Size::Percents(50));
+		assert_eq!(position(&clipped, flow, flow, SCREEN), Rect::new(15, 0, 5, 5));
+		let outside = element(Position::Absolute, (30, 20), Size::Chars(4), Size::Chars(4));
+		assert!(position(&outside, flow, flow, SCREEN).is_empty());
+	}
+
+	#[test]
+	fn position_centered() {
+		let flow = Rect::new(1, 1, 2, 2);
+		let centered = element(Position::Centered, (5, 5), Size::Chars(6), Size::Chars(4));
+		assert_eq!(position(&centered, flow, flow, SCREEN), Rect::new(7, 3, 6, 4));
+		let percents = element(Position::Centered, (0, 0), Size::Percents(50), Size::Percents(50));
+		assert_eq!(position(&percents, flow, flow, SCREEN), Rect::new(5, 2, 10, 5));
+		let larger = element(Position::Centered, (0, 0), Size::Chars(200), Size::Auto);
+		assert_eq!(position(&larger, flow, flow, SCREEN), SCREEN);
+	}
+
+	#[test]
+	fn position_margin() {
+		let flow = Rect::new(0, 0, 10, 6);
+		let mut spaced = element(Position::Relative, (0, 0), Size::Auto, Size::Auto);
+		spaced.set_margin(Sides::symmetric(1, 2));
+		assert_eq!(position(&spaced, flow, flow, SCREEN), Rect::new(2, 1, 6, 4));
+		spaced.set_margin(Sides::all(5));
+		assert!(position(&spaced, Rect::new(0, 0, 6, 4), flow, SCREEN).is_empty());
+	}
+}
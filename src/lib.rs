@@ -1 +1,19 @@
+pub mod backend;
+pub mod border;
+pub mod buffer;
 pub mod components;
+pub mod config;
+pub mod error;
+pub mod event;
+pub mod focus;
+pub mod layout;
+mod macros;
+pub mod registry;
+pub mod render;
+pub mod style;
+#[cfg(unix)]
+pub mod terminal;
+pub mod theme;
+pub mod unicode;
+
+pub use error::Error;
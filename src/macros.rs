@@ -0,0 +1,63 @@
+//! The macros module contains the tuim! macro, describing a tree of UI
+//! elements as it's displayed.
+
+/// Builds a tree of UI elements from its description.
+///
+/// Each element is written as a kind, such as `Container` or `Table`, its
+/// properties between parentheses and its children between braces. The
+/// parentheses can be left out when there is no property, and the braces
+/// when there is no child. An element built beforehand is given between
+/// parentheses, e.g. `(popup)`.
+///
+/// The kinds are the types in scope having a `builder` function, and the
+/// properties are the methods of the builder. The macro expands to calls
+/// to the builders, so an unknown property is an error at compile time.
+///
+/// Returns the root element, or the first error returned by a builder.
+///
+/// ```
+/// use tuim::tuim;
+/// use tuim::border::BorderSet;
+/// use tuim::components::{Container, Layout, Size};
+/// use tuim::components::table::Table;
+///
+/// let root = tuim! {
+///     Container(layout: Layout::Horizontal, title: "Dashboard") {
+///         Table(headers: &["Job", "State"], width: Size::Percents(40), border: BorderSet::SINGLE),
+///         Container(layout: Layout::Vertical) {
+///             Table(headers: &["Host"], title: "Hosts"),
+///             Table(headers: &["Alert"], title: "Alerts"),
+///         },
+///     }
+/// };
+/// assert!(root.is_ok());
+/// ```
+///
+/// ```compile_fail
+/// use tuim::tuim;
+/// use tuim::components::table::Table;
+///
+/// let table = tuim! { Table(colour: "red") };
+/// ```
+#[macro_export]
+macro_rules! tuim {
+	(@children $builder:expr ;) => {
+		$builder
+	};
+	(@children $builder:expr ; ( $element:expr ) $(, $($rest:tt)*)?) => {
+		$crate::tuim!(@children $builder.child($element) ; $($($rest)*)?)
+	};
+	(@children $builder:expr ; $kind:ident $(( $($properties:tt)* ))? $({ $($children:tt)* })? $(, $($rest:tt)*)?) => {
+		$crate::tuim!(
+			@children $builder.child($crate::tuim!($kind $(( $($properties)* ))? $({ $($children)* })?)?) ;
+			$($($rest)*)?
+		)
+	};
+	($kind:ident $(( $($property:ident : $value:expr),* $(,)? ))? $({ $($children:tt)* })?) => {
+		(|| -> ::std::result::Result<_, $crate::Error> {
+			let builder = $kind::builder()$($(.$property($value))*)?;
+			let builder = $crate::tuim!(@children builder ; $($($children)*)?);
+			builder.build()
+		})()
+	};
+}
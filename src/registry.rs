@@ -0,0 +1,567 @@
+//! The registry module builds trees of UI elements from their description
+//! in a TOML or JSON file, or a YAML file with the yaml feature, so that
+//! they can be rearranged without a rebuild.
+//!
+//! Each element is a table whose type key gives the name of the widget,
+//! its other keys being the properties of the element:
+//!
+//! ```toml
+//! type = "container"
+//! layout = "horizontal"
+//! title = "Dashboard"
+//! border = "rounded"
+//!
+//! [[children]]
+//! type = "table"
+//! width = "40%"
+//! headers = ["Job", "State"]
+//! rows = [["backup", "done"]]
+//!
+//! [[children]]
+//! type = "container"
+//! layout = "vertical"
+//! padding = [0, 1]
+//!
+//! [[children.children]]
+//! type = "table"
+//! title = "Hosts"
+//! headers = ["Host"]
+//! ```
+//!
+//! The properties which are unknown to the widget are rejected. The
+//! properties of every element are title, title_align, footer,
+//! footer_align, width, height, position, offset, z_index, border,
+//! border_sides, padding, margin, style, border_style and title_style.
+//! The widgets read their own properties:
+//! - container: layout, children and active_tab.
+//! - table: headers, rows, items_by_page, cell_padding, header_style,
+//!   row_style, alt_row_style and selected_style.
+//! - popup: content and dim.
+//!
+//! A size is "auto", a number of chars or a percentage such as "40%".
+//! The sides, e.g. of the padding, are a single number, [vertical,
+//! horizontal] or [top, right, bottom, left]. The styles and the borders
+//! are written as in the themes. With the serde feature, the sizes,
+//! layouts and positions are serialized in the same format.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::components::popup::Popup;
+use crate::components::table::Table;
+use crate::components::{Container, Layout, Position, Size, UIElement};
+use crate::config::Value;
+use crate::layout::{Align, Sides};
+use crate::theme::{invalid, parse_border, parse_style};
+use crate::Error;
+
+/// A function building a widget from its description.
+///
+/// The registry is given to build the children of the widget. The
+/// properties common to every element are applied afterwards.
+pub type Factory = Box<dyn Fn(&Value, &Registry) -> Result<Box<dyn UIElement>, Error>>;
+
+/// The properties of every element, and the type.
+const COMMON_PROPERTIES: &[&str] = &[
+	"type", "title", "title_align", "footer", "footer_align", "width", "height", "position",
+	"offset", "z_index", "border", "border_sides", "padding", "margin", "style", "border_style",
+	"title_style",
+];
+
+/// A widget which can be described.
+struct Widget {
+	/// The properties read by the factory, besides the common ones.
+	properties: Vec<String>,
+	/// Builds the widget from its description.
+	factory: Factory,
+}
+
+/// The widgets which can be described, by type name.
+pub struct Registry {
+	/// The widgets, by type name.
+	widgets: HashMap<String, Widget>,
+}
+
+impl Default for Registry {
+	/// Returns a registry with the widgets of the library: container,
+	/// table and popup.
+	fn default() -> Self {
+		let mut registry = Self::empty();
+		registry.register("container", &["layout", "children", "active_tab"], build_container);
+		registry.register("table", &[
+			"headers", "rows", "items_by_page", "cell_padding", "header_style", "row_style",
+			"alt_row_style", "selected_style",
+		], build_table);
+		registry.register("popup", &["content", "dim"], build_popup);
+		registry
+	}
+}
+
+impl Registry {
+	/// Creates a new Registry with the widgets of the library: container,
+	/// table and popup.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new Registry without any widget.
+	pub fn empty() -> Self {
+		Self {
+			widgets: HashMap::new(),
+		}
+	}
+
+	/// Registers a widget, replacing the one with the same name.
+	///
+	/// # Parameters
+	/// - name: the name of the widget, given by the type key.
+	/// - properties: the properties read by the factory, besides the
+	///   common ones. The other properties are rejected.
+	/// - factory: builds the widget from its description.
+	pub fn register<F>(&mut self, name: &str, properties: &[&str], factory: F)
+	where
+		F: Fn(&Value, &Registry) -> Result<Box<dyn UIElement>, Error> + 'static,
+	{
+		let widget = Widget {
+			properties: properties.iter().map(|property| property.to_string()).collect(),
+			factory: Box::new(factory),
+		};
+		self.widgets.insert(name.to_string(), widget);
+	}
+
+	/// Builds an element and its children from their description.
+	///
+	/// Returns an error when a type isn't registered, when a property is
+	/// unknown, or when a property has an invalid value.
+	pub fn build(&self, description: &Value) -> Result<Box<dyn UIElement>, Error> {
+		let Some(entries) = description.as_table() else {
+			return Err(invalid("", "expected a table describing an element"));
+		};
+		let name = description.get("type")
+			.ok_or_else(|| invalid("type", "missing type"))?
+			.as_str()
+			.ok_or_else(|| invalid("type", "expected a string"))?;
+		let widget = self.widgets.get(name)
+			.ok_or_else(|| invalid("type", &format!("unknown widget '{}'", name)))?;
+		for (key, _) in entries {
+			if !COMMON_PROPERTIES.contains(&key.as_str()) && !widget.properties.contains(key) {
+				return Err(invalid(key, &format!("unknown property of {}", name)));
+			}
+		}
+		let mut element = (widget.factory)(description, self)?;
+		apply_common(description, element.as_mut())?;
+		Ok(element)
+	}
+
+	/// Builds a tree of elements described in TOML.
+	pub fn from_toml(&self, text: &str) -> Result<Box<dyn UIElement>, Error> {
+		self.build(&Value::parse_toml(text)?)
+	}
+
+	/// Builds a tree of elements described in JSON.
+	pub fn from_json(&self, text: &str) -> Result<Box<dyn UIElement>, Error> {
+		self.build(&Value::parse_json(text)?)
+	}
+
+	/// Builds a tree of elements described in YAML.
+	#[cfg(feature = "yaml")]
+	pub fn from_yaml(&self, text: &str) -> Result<Box<dyn UIElement>, Error> {
+		self.build(&Value::parse_yaml(text)?)
+	}
+
+	/// Builds a tree of elements described in a file.
+	///
+	/// Files ending with .json are parsed as JSON, the ones ending with
+	/// .yaml or .yml as YAML with the yaml feature, the other ones as TOML.
+	pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Box<dyn UIElement>, Error> {
+		self.build(&Value::load(path.as_ref())?)
+	}
+}
+
+/// Applies the properties common to every element.
+fn apply_common(description: &Value, element: &mut dyn UIElement) -> Result<(), Error> {
+	if let Some(title) = string(description, "title")? {
+		element.set_title(title);
+	}
+	if let Some(align) = optional(description, "title_align", parse_align)? {
+		element.set_title_align(align);
+	}
+	if let Some(footer) = string(description, "footer")? {
+		element.set_footer(footer);
+	}
+	if let Some(align) = optional(description, "footer_align", parse_align)? {
+		element.set_footer_align(align);
+	}
+	if let Some(width) = optional(description, "width", parse_size)? {
+		element.set_width(width);
+	}
+	if let Some(height) = optional(description, "height", parse_size)? {
+		element.set_height(height);
+	}
+	if let Some(position) = optional(description, "position", parse_position)? {
+		element.set_position(position);
+	}
+	if let Some(offset) = optional(description, "offset", parse_offset)? {
+		element.set_offset(offset.0, offset.1);
+	}
+	if let Some(z_index) = optional(description, "z_index", integer)? {
+		element.set_z_index(z_index);
+	}
+	if let Some(border) = optional(description, "border", parse_border)? {
+		element.set_border(border);
+	}
+	if let Some(sides) = optional(description, "border_sides", parse_border_sides)? {
+		element.set_border_sides(sides);
+	}
+	if let Some(padding) = optional(description, "padding", parse_sides)? {
+		element.set_padding(padding);
+	}
+	if let Some(margin) = optional(description, "margin", parse_sides)? {
+		element.set_margin(margin);
+	}
+	if let Some(style) = optional(description, "style", parse_style)? {
+		element.set_style(style);
+	}
+	if let Some(style) = optional(description, "border_style", parse_style)? {
+		element.set_border_style(style);
+	}
+	if let Some(style) = optional(description, "title_style", parse_style)? {
+		element.set_title_style(style);
+	}
+	Ok(())
+}
+
+/// Builds a Container and its children.
+fn build_container(description: &Value, registry: &Registry) -> Result<Box<dyn UIElement>, Error> {
+	let layout = optional(description, "layout", parse_layout)?.unwrap_or(Layout::Vertical);
+	let children = match description.get("children") {
+		Some(children) => children.as_array()
+			.ok_or_else(|| invalid("children", "expected an array of elements"))?
+			.iter()
+			.map(|child| registry.build(child))
+			.collect::<Result<Vec<_>, Error>>()?,
+		None => Vec::new(),
+	};
+	let mut builder = Container::builder().layout(layout).children(children);
+	if let Some(active_tab) = optional(description, "active_tab", integer)? {
+		builder = builder.active_tab(active_tab);
+	}
+	Ok(Box::new(builder.build()?))
+}
+
+/// Builds a Table from its headers and its rows.
+fn build_table(description: &Value, _registry: &Registry) -> Result<Box<dyn UIElement>, Error> {
+	let headers = optional(description, "headers", parse_strings)?.unwrap_or_default();
+	let rows = match description.get("rows") {
+		Some(rows) => rows.as_array()
+			.ok_or_else(|| invalid("rows", "expected an array of lines"))?
+			.iter()
+			.map(|row| parse_strings("rows", row))
+			.collect::<Result<Vec<_>, Error>>()?,
+		None => Vec::new(),
+	};
+	let mut builder = Table::builder().headers(&headers).rows(rows);
+	if let Some(items_by_page) = optional(description, "items_by_page", integer)? {
+		builder = builder.items_by_page(items_by_page);
+	}
+	if let Some(padding) = optional(description, "cell_padding", parse_sides)? {
+		builder = builder.cell_padding_sides(padding);
+	}
+	if let Some(style) = optional(description, "header_style", parse_style)? {
+		builder = builder.header_style(style);
+	}
+	if let Some(style) = optional(description, "row_style", parse_style)? {
+		builder = builder.row_style(style);
+	}
+	if let Some(style) = optional(description, "alt_row_style", parse_style)? {
+		builder = builder.alt_row_style(style);
+	}
+	if let Some(style) = optional(description, "selected_style", parse_style)? {
+		builder = builder.selected_style(style);
+	}
+	Ok(Box::new(builder.build()?))
+}
+
+/// Builds a Popup around its content.
+fn build_popup(description: &Value, registry: &Registry) -> Result<Box<dyn UIElement>, Error> {
+	let content = description.get("content")
+		.ok_or_else(|| invalid("content", "missing content"))?;
+	let mut popup = Popup::new(registry.build(content)?);
+	if let Some(dim) = description.get("dim") {
+		popup.set_dim(dim.as_bool().ok_or_else(|| invalid("dim", "expected a boolean"))?);
+	}
+	Ok(Box::new(popup))
+}
+
+/// Parses a property, if it's given.
+fn optional<T>(description: &Value, key: &str, parse: fn(&str, &Value) -> Result<T, Error>) -> Result<Option<T>, Error> {
+	description.get(key).map(|value| parse(key, value)).transpose()
+}
+
+/// Returns a property which must be a string, if it's given.
+fn string<'a>(description: &'a Value, key: &str) -> Result<Option<&'a str>, Error> {
+	description.get(key)
+		.map(|value| value.as_str().ok_or_else(|| invalid(key, "expected a string")))
+		.transpose()
+}
+
+/// Parses an integer, which must fit in the expected type.
+fn integer<T: TryFrom<i64>>(key: &str, value: &Value) -> Result<T, Error> {
+	value.as_integer()
+		.and_then(|integer| T::try_from(integer).ok())
+		.ok_or_else(|| invalid(key, "expected a positive integer in range"))
+}
+
+/// Parses an array of strings, the numbers being written as strings.
+fn parse_strings(key: &str, value: &Value) -> Result<Vec<String>, Error> {
+	let values = value.as_array().ok_or_else(|| invalid(key, "expected an array of strings"))?;
+	values.iter()
+		.map(|value| match value {
+			Value::String(text) => Ok(text.clone()),
+			Value::Integer(integer) => Ok(integer.to_string()),
+			Value::Float(float) => Ok(float.to_string()),
+			Value::Boolean(boolean) => Ok(boolean.to_string()),
+			_ => Err(invalid(key, "expected an array of strings")),
+		})
+		.collect()
+}
+
+/// Parses a size: auto, a number of chars or a percentage.
+fn parse_size(key: &str, value: &Value) -> Result<Size, Error> {
+	match value {
+		Value::Integer(_) => integer(key, value).map(Size::Chars),
+		Value::String(text) if text == "auto" => Ok(Size::Auto),
+		Value::String(text) => text.strip_suffix('%')
+			.and_then(|percents| percents.trim().parse().ok())
+			.filter(|percents| *percents <= 100)
+			.map(Size::Percents)
+			.ok_or_else(|| invalid(key, "expected auto, a number of chars or a percentage")),
+		_ => Err(invalid(key, "expected auto, a number of chars or a percentage")),
+	}
+}
+
+/// Parses a layout: horizontal, vertical or tabbed.
+fn parse_layout(key: &str, value: &Value) -> Result<Layout, Error> {
+	match value.as_str() {
+		Some("horizontal") => Ok(Layout::Horizontal),
+		Some("vertical") => Ok(Layout::Vertical),
+		Some("tabbed") => Ok(Layout::Tabbed),
+		_ => Err(invalid(key, "expected horizontal, vertical or tabbed")),
+	}
+}
+
+/// Parses a position: relative, absolute or centered.
+fn parse_position(key: &str, value: &Value) -> Result<Position, Error> {
+	match value.as_str() {
+		Some("relative") => Ok(Position::Relative),
+		Some("absolute") => Ok(Position::Absolute),
+		Some("centered") => Ok(Position::Centered),
+		_ => Err(invalid(key, "expected relative, absolute or centered")),
+	}
+}
+
+/// Parses an alignment: left, center or right.
+fn parse_align(key: &str, value: &Value) -> Result<Align, Error> {
+	match value.as_str() {
+		Some("left") => Ok(Align::Left),
+		Some("center") => Ok(Align::Center),
+		Some("right") => Ok(Align::Right),
+		_ => Err(invalid(key, "expected left, center or right")),
+	}
+}
+
+/// Parses an offset: [x, y].
+fn parse_offset(key: &str, value: &Value) -> Result<(u16, u16), Error> {
+	match value.as_array() {
+		Some([x, y]) => Ok((integer(key, x)?, integer(key, y)?)),
+		_ => Err(invalid(key, "expected [x, y]")),
+	}
+}
+
+/// Parses the sides of a space: a single number, [vertical, horizontal]
+/// or [top, right, bottom, left].
+fn parse_sides(key: &str, value: &Value) -> Result<Sides<u16>, Error> {
+	if let Value::Integer(_) = value {
+		return integer(key, value).map(Sides::all);
+	}
+	let values = value.as_array().ok_or_else(|| invalid(key, "expected a number or an array of numbers"))?;
+	let numbers = values.iter()
+		.map(|value| integer(key, value))
+		.collect::<Result<Vec<u16>, Error>>()?;
+	match numbers[..] {
+		[vertical, horizontal] => Ok(Sides::symmetric(vertical, horizontal)),
+		[top, right, bottom, left] => Ok(Sides::new(top, right, bottom, left)),
+		_ => Err(invalid(key, "expected [vertical, horizontal] or [top, right, bottom, left]")),
+	}
+}
+
+/// Parses the sides the border is drawn on: an array of top, right,
+/// bottom and left.
+fn parse_border_sides(key: &str, value: &Value) -> Result<Sides<bool>, Error> {
+	let names = value.as_array().ok_or_else(|| invalid(key, "expected an array of sides"))?;
+	let mut sides = Sides::all(false);
+	for name in names {
+		let side = match name.as_str() {
+			Some("top") => &mut sides.top,
+			Some("right") => &mut sides.right,
+			Some("bottom") => &mut sides.bottom,
+			Some("left") => &mut sides.left,
+			_ => return Err(invalid(key, "expected top, right, bottom or left")),
+		};
+		*side = true;
+	}
+	Ok(sides)
+}
+
+/// Reads a value with serde, written as in the descriptions, so that both
+/// share a single format.
+#[cfg(feature = "serde")]
+fn deserialize<'de, D, T>(deserializer: D, parse: fn(&str, &Value) -> Result<T, Error>) -> Result<T, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let value = <Value as serde::Deserialize>::deserialize(deserializer)?;
+	parse("", &value).map_err(|error| match error {
+		Error::InvalidValue { message, .. } => serde::de::Error::custom(message),
+		error => serde::de::Error::custom(error),
+	})
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Size {
+	/// Writes auto, a number of chars or a percentage such as "40%".
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Size::Auto => serializer.serialize_str("auto"),
+			Size::Chars(chars) => serializer.serialize_u8(*chars),
+			Size::Percents(percents) => serializer.collect_str(&format_args!("{}%", percents)),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Size {
+	/// Reads a size as `parse_size` does.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserialize(deserializer, parse_size)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Layout {
+	/// Writes horizontal, vertical or tabbed.
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(match self {
+			Layout::Horizontal => "horizontal",
+			Layout::Vertical => "vertical",
+			Layout::Tabbed => "tabbed",
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Layout {
+	/// Reads a layout as `parse_layout` does.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserialize(deserializer, parse_layout)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Position {
+	/// Writes relative, absolute or centered.
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(match self {
+			Position::Relative => "relative",
+			Position::Absolute => "absolute",
+			Position::Centered => "centered",
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Position {
+	/// Reads a position as `parse_position` does.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserialize(deserializer, parse_position)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Returns the key and the message of the error of a description.
+	fn error(text: &str) -> (String, String) {
+		match Registry::new().from_toml(text) {
+			Err(Error::InvalidValue { key, message }) => (key, message),
+			Err(error) => panic!("expected an invalid value, got {:?}", error),
+			Ok(_) => panic!("expected an invalid value"),
+		}
+	}
+
+	#[test]
+	fn building_a_tree() {
+		let root = Registry::new().from_toml("type = 'container'\nlayout = 'tabbed'\ntitle = 'Root'\n\
+			[[children]]\ntype = 'table'\ntitle = 'Jobs'\nheaders = ['Job']\nrows = [['backup']]\n\
+			[[children]]\ntype = 'popup'\ndim = true\ncontent = { type = 'container' }").unwrap();
+		assert_eq!(root.title(), "Root");
+		assert!(matches!(root.layout(), Some(Layout::Tabbed)));
+		assert_eq!(root.children().len(), 2);
+		assert_eq!(root.children()[0].title(), "Jobs");
+		assert!(root.children()[1].dims_background());
+	}
+
+	#[test]
+	fn unknown_properties_are_rejected() {
+		assert_eq!(error("type = 'container'\ntitel = 'Root'"), ("titel".to_string(), "unknown property of container".to_string()));
+		assert_eq!(error("type = 'popup'\nlayout = 'vertical'\ncontent = { type = 'container' }").0, "layout");
+		assert_eq!(error("type = 'container'\n[[children]]\ntype = 'table'\nrow = []").0, "row");
+		assert_eq!(error("type = 'table'\nstyle = { colour = 'red' }").0, "style.colour");
+		assert_eq!(error("type = 'gauge'").1, "unknown widget 'gauge'");
+
+		let mut registry = Registry::empty();
+		registry.register("gauge", &["value"], |_, _| Ok(Box::new(Container::new(Vec::new(), Layout::Vertical))));
+		assert!(registry.from_toml("type = 'gauge'\nvalue = 3\ntitle = 'Load'").is_ok());
+		assert!(registry.from_toml("type = 'gauge'\nvalu = 3").is_err());
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn yaml_descriptions() {
+		let root = Registry::new().from_yaml("type: container\nlayout: horizontal\nchildren:\n  - type: table\n    width: 40%\n    headers: [Job]\n").unwrap();
+		assert!(matches!(root.children()[0].width(), Size::Percents(40)));
+		assert!(Registry::new().from_yaml("type: container\ntitel: Root\n").is_err());
+		match Value::parse_yaml("a: 1\nb: [\n") {
+			Err(Error::Parse { line, .. }) => assert_eq!(line, 3),
+			result => panic!("expected a parse error, got {:?}", result),
+		}
+		assert!(Value::parse_yaml("a: ~").is_err());
+	}
+
+	#[cfg(feature = "yaml")]
+	#[test]
+	fn serde_sizes_layouts_and_positions() {
+		assert_eq!(serde_yaml::to_string(&Size::Percents(40)).unwrap(), "40%\n");
+		assert_eq!(serde_yaml::to_string(&Size::Chars(12)).unwrap(), "12\n");
+		assert_eq!(serde_yaml::to_string(&Layout::Tabbed).unwrap(), "tabbed\n");
+		assert!(matches!(serde_yaml::from_str("40%").unwrap(), Size::Percents(40)));
+		assert!(matches!(serde_yaml::from_str("12").unwrap(), Size::Chars(12)));
+		assert!(matches!(serde_yaml::from_str("auto").unwrap(), Size::Auto));
+		assert!(serde_yaml::from_str::<Size>("120%").is_err());
+		assert!(serde_yaml::from_str::<Size>("!percents 40").is_err());
+		assert!(matches!(serde_yaml::from_str("tabbed").unwrap(), Layout::Tabbed));
+		assert_eq!(serde_yaml::from_str::<Position>("centered").unwrap(), Position::Centered);
+		assert!(serde_yaml::from_str::<Position>("middle").is_err());
+
+		// What serde writes, the registry reads.
+		for size in [Size::Auto, Size::Chars(12), Size::Percents(40)] {
+			let text = serde_yaml::to_string(&size).unwrap();
+			let value = Value::parse_yaml(&format!("width: {}", text)).unwrap();
+			assert_eq!(serde_yaml::to_string(&parse_size("width", value.get("width").unwrap()).unwrap()).unwrap(), text);
+		}
+		let json = serde_json::to_string(&[Size::Percents(40), Size::Chars(3)]).unwrap();
+		assert_eq!(json, r#"["40%",3]"#);
+		assert!(Registry::new().from_json(&format!(r#"{{"type": "container", "width": {}}}"#, serde_json::to_string(&Size::Percents(40)).unwrap())).is_ok());
+	}
+}
@@ -0,0 +1,576 @@
+//! The render module draws a tree of UI elements on the terminal.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::backend::Backend;
+use crate::buffer::Buffer;
+use crate::components::UIElement;
+use crate::layout::{self, Align, Rect, Sides};
+use crate::style::Style;
+use crate::unicode;
+
+/// Draws a tree of UI elements on a backend.
+///
+/// The renderer keeps the previous frame: only the elements which have
+/// been updated, or moved, are rendered again, with the parts of the
+/// elements around them they changed, and only the cells which changed
+/// are sent to the backend.
+pub struct Renderer<B: Backend> {
+	/// Where the frames are written.
+	backend: B,
+	/// The last frame sent to the backend.
+	previous: Buffer,
+	/// The area, the covered area and the z index of each element in the
+	/// last frame, by path in the tree.
+	areas: HashMap<Vec<usize>, (Rect, Rect, u16)>,
+	/// The path and the area of each element in the last frame,
+	/// in the order they have been composited.
+	stack: Vec<(Vec<usize>, Rect)>,
+	/// Whether the next frame must be fully rendered.
+	invalidated: bool,
+}
+
+impl<B: Backend> Renderer<B> {
+	/// Creates a new Renderer.
+	///
+	/// # Parameters
+	/// - backend: where the frames are written.
+	pub fn new(backend: B) -> Self {
+		Self {
+			backend,
+			previous: Buffer::new(0, 0),
+			areas: HashMap::new(),
+			stack: Vec::new(),
+			invalidated: true,
+		}
+	}
+
+	/// Returns the backend.
+	pub fn backend(&self) -> &B {
+		&self.backend
+	}
+
+	/// Returns the backend mutably, e.g. to resize it.
+	pub fn backend_mut(&mut self) -> &mut B {
+		&mut self.backend
+	}
+
+	/// Forces the next frame to be fully rendered and written.
+	pub fn invalidate(&mut self) {
+		self.invalidated = true;
+	}
+
+	/// Renders the root element and its children, and writes the
+	/// changes to the backend.
+	///
+	/// Once the frame is written, the elements are marked as not updated.
+	pub fn render(&mut self, root: &mut dyn UIElement) -> io::Result<()> {
+		let (width, height) = self.backend.size();
+		if self.invalidated || self.previous.width() != width || self.previous.height() != height {
+			self.backend.clear()?;
+			self.previous = Buffer::new(width, height);
+			self.areas.clear();
+			self.invalidated = true;
+		}
+
+		let mut buffer = self.previous.clone();
+		self.redraw(root, &mut buffer);
+		let changes = buffer.diff(&self.previous);
+		self.backend.draw(changes.into_iter())?;
+		self.backend.flush()?;
+
+		self.previous = buffer;
+		self.invalidated = false;
+		clear_updated(root);
+		Ok(())
+	}
+
+	/// Renders the elements which need it in the buffer.
+	///
+	/// The elements are composited by z index, the ones with a greater
+	/// z index being painted last. The z index of an element is relative
+	/// to its container, so raising a container raises all its elements.
+	///
+	/// The areas of the elements which have been updated, moved, raised
+	/// or removed are cleared, where they were and where they are, and
+	/// these elements are painted again. The other elements over the
+	/// cleared areas, such as the containers below, are painted again
+	/// only inside these areas, so that the children are painted over the
+	/// style of their container without rendering the whole tree again.
+	/// The elements over a painted area are painted again there too.
+	///
+	/// The areas and the z indexes of the elements are then kept for the
+	/// next frame.
+	fn redraw(&mut self, root: &dyn UIElement, buffer: &mut Buffer) {
+		let screen = buffer.area();
+		let mut placements = place(root, root_area(root, screen), screen);
+		placements.sort_by_key(|placement| placement.z_index);
+		let areas: HashMap<Vec<usize>, (Rect, Rect, u16)> = placements.iter()
+			.map(|placement| (placement.path.clone(), (placement.area, placement.covered, placement.z_index)))
+			.collect();
+		let changed = |placement: &Placement| {
+			self.invalidated
+				|| placement.element.updated()
+				|| self.areas.get(&placement.path) != Some(&(placement.area, placement.covered, placement.z_index))
+		};
+		let mut dirty: Vec<Rect> = self.areas.iter()
+			.filter(|(path, areas_before)| areas.get(*path) != Some(*areas_before))
+			.map(|(_, (_, covered, _))| *covered)
+			.collect();
+		dirty.extend(placements.iter()
+			.filter(|placement| changed(placement))
+			.map(|placement| placement.covered));
+		for area in &dirty {
+			buffer.clear(*area);
+		}
+
+		let mut painted: Vec<Rect> = Vec::new();
+		for placement in &placements {
+			let covered = placement.covered;
+			if changed(placement) {
+				paint(placement.element, placement.area, buffer);
+				painted.push(covered);
+				continue;
+			}
+			let regions: Vec<Rect> = dirty.iter()
+				.chain(&painted)
+				.map(|area| area.intersection(&covered))
+				.filter(|region| !region.is_empty())
+				.collect();
+			if !regions.is_empty() {
+				buffer.clip(regions.clone());
+				paint(placement.element, placement.area, buffer);
+				buffer.unclip();
+				painted.extend(regions);
+			}
+		}
+		self.areas = areas;
+		self.stack = placements.into_iter()
+			.map(|placement| (placement.path, placement.area))
+			.collect();
+	}
+
+	/// Returns the element displayed at the given position in the last frame.
+	///
+	/// The element is given by the indexes of the children leading to it
+	/// from the root. When elements overlap, the topmost one is returned.
+	pub fn element_at(&self, x: u16, y: u16) -> Option<Vec<usize>> {
+		self.stack.iter()
+			.rev()
+			.find(|(_, area)| area.contains(x, y))
+			.map(|(path, _)| path.clone())
+	}
+}
+
+/// An element placed on the screen.
+struct Placement<'a> {
+	/// The indexes of the children leading to the element from the root.
+	path: Vec<usize>,
+	/// The element.
+	element: &'a dyn UIElement,
+	/// The area of the element, border included.
+	area: Rect,
+	/// The area changed by painting the element.
+	///
+	/// It's the whole screen for the elements dimming the background.
+	covered: Rect,
+	/// The z index used to composite the element.
+	///
+	/// It's the sum of the z indexes of the element and its containers.
+	z_index: u16,
+}
+
+/// Computes the area of every element in the tree.
+///
+/// The elements are returned parents first, in the order of the tree.
+/// The children which aren't displayed, e.g. the inactive tabs, are left
+/// out along with their own children.
+///
+/// # Parameters
+/// - root: the root element.
+/// - area: the area of the root element.
+/// - screen: the area of the whole screen.
+fn place(root: &dyn UIElement, area: Rect, screen: Rect) -> Vec<Placement<'_>> {
+	fn walk<'a>(element: &'a dyn UIElement, area: Rect, screen: Rect, parent_z_index: u16, path: &mut Vec<usize>, placements: &mut Vec<Placement<'a>>) {
+		let z_index = parent_z_index.saturating_add(element.z_index() as u16);
+		let covered = if element.dims_background() && !area.is_empty() { screen } else { area };
+		placements.push(Placement { path: path.clone(), element, area, covered, z_index });
+		let inner = inner_area(element, area);
+		for (index, (child, flow)) in element.children().iter().zip(element.child_areas(inner)).enumerate() {
+			if !element.child_visible(index) {
+				continue;
+			}
+			let child_area = layout::position(child.as_ref(), flow, inner, screen);
+			path.push(index);
+			walk(child.as_ref(), child_area, screen, z_index, path, placements);
+			path.pop();
+		}
+	}
+	let mut placements = Vec::new();
+	walk(root, area, screen, 0, &mut Vec::new(), &mut placements);
+	placements
+}
+
+/// Marks an element and all its children as not updated.
+fn clear_updated(element: &mut dyn UIElement) {
+	element.set_updated(false);
+	for child in element.children_mut() {
+		clear_updated(child.as_mut());
+	}
+}
+
+/// Computes the area of the root element on the screen.
+pub fn root_area(root: &dyn UIElement, screen: Rect) -> Rect {
+	let flow = Rect::new(
+		screen.x,
+		screen.y,
+		root.width().resolve(screen.width).unwrap_or(screen.width),
+		root.height().resolve(screen.height).unwrap_or(screen.height),
+	);
+	layout::position(root, flow, screen, screen)
+}
+
+/// Draws an element and its children in the buffer, by z index.
+///
+/// # Parameters
+/// - element: the element to draw.
+/// - area: the area given to the element, border included.
+/// - buffer: where the element is drawn.
+pub fn draw(element: &dyn UIElement, area: Rect, buffer: &mut Buffer) {
+	let mut placements = place(element, area, buffer.area());
+	placements.sort_by_key(|placement| placement.z_index);
+	for placement in placements {
+		paint(placement.element, placement.area, buffer);
+	}
+}
+
+/// Draws the border, the title and the content of a single element.
+///
+/// When the element dims the background, the whole buffer is dimmed first.
+/// When the element has a style, its area is filled with it.
+fn paint(element: &dyn UIElement, area: Rect, buffer: &mut Buffer) {
+	if area.is_empty() {
+		return;
+	}
+	if element.dims_background() {
+		let screen = buffer.area();
+		buffer.dim(screen);
+		buffer.clear(area);
+	}
+	if element.style() != Style::default() {
+		buffer.fill(area, element.style());
+	}
+	draw_frame(element, area, buffer);
+	element.render(inner_area(element, area), buffer);
+}
+
+/// Returns the area inside the border and the padding of an element.
+///
+/// A border made of spaces, or left out on a side, is not displayed and
+/// takes no space. When there is no border on the top, the title takes
+/// its own line, and so does the footer without border on the bottom.
+pub fn inner_area(element: &dyn UIElement, area: Rect) -> Rect {
+	let sides = drawn_sides(element);
+	let title = !sides.top && !element.title().is_empty();
+	let footer = !sides.bottom && !element.footer().is_empty();
+	let frame = Sides::new(
+		(sides.top || title) as u16,
+		sides.right as u16,
+		(sides.bottom || footer) as u16,
+		sides.left as u16,
+	);
+	area.shrink(frame).shrink(element.padding())
+}
+
+/// Returns the sides the border of an element is drawn on: the ones
+/// asked for, when the border has a char for them.
+fn drawn_sides(element: &dyn UIElement) -> Sides<bool> {
+	let border = element.border();
+	let sides = element.border_sides();
+	Sides::new(
+		sides.top && border.has_horizontal(),
+		sides.right && border.has_vertical(),
+		sides.bottom && border.has_horizontal(),
+		sides.left && border.has_vertical(),
+	)
+}
+
+/// Draws the border, the title and the footer of an element, in their
+/// style applied over the style of the element.
+///
+/// Only the sides of the border asked for are drawn, and the corners
+/// where two of them meet. The title is drawn on the top line and the
+/// footer on the bottom one, between the vertical sides. The title of
+/// the focused element is bold.
+fn draw_frame(element: &dyn UIElement, area: Rect, buffer: &mut Buffer) {
+	let border = element.border();
+	let sides = drawn_sides(element);
+	let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+	let border_style = element.style().patch(element.border_style());
+
+	for (drawn, y) in [(sides.top, area.y), (sides.bottom, bottom)] {
+		if drawn {
+			for x in area.x..area.right() {
+				buffer.set(x, y, border.horizontal, border_style);
+			}
+		}
+	}
+	for (drawn, x) in [(sides.left, area.x), (sides.right, right)] {
+		if drawn {
+			for y in area.y..area.bottom() {
+				buffer.set(x, y, border.vertical, border_style);
+			}
+		}
+	}
+	let corners = [
+		(sides.top && sides.left, area.x, area.y, border.top_left),
+		(sides.top && sides.right, right, area.y, border.top_right),
+		(sides.bottom && sides.left, area.x, bottom, border.bottom_left),
+		(sides.bottom && sides.right, right, bottom, border.bottom_right),
+	];
+	for (drawn, x, y, corner) in corners {
+		if drawn {
+			buffer.set(x, y, corner, border_style);
+		}
+	}
+
+	let x = area.x + sides.left as u16;
+	let width = area.width.saturating_sub(sides.left as u16 + sides.right as u16);
+	let style = element.style().patch(element.title_style());
+	let title_style = if element.focused() { style.bold() } else { style };
+	draw_label(element.title(), element.title_align(), Rect::new(x, area.y, width, 1), title_style, buffer);
+	if area.height > 1 {
+		draw_label(element.footer(), element.footer_align(), Rect::new(x, bottom, width, 1), style, buffer);
+	}
+}
+
+/// Prints a title or a footer, aligned in the given line.
+///
+/// A text wider than the line is cut, and ends with an ellipsis.
+fn draw_label(text: &str, align: Align, line: Rect, style: Style, buffer: &mut Buffer) {
+	if text.is_empty() || line.width == 0 {
+		return;
+	}
+	let length = unicode::width(text);
+	if length <= line.width {
+		buffer.print(line.x + align.indent(length, line.width), line.y, text, length, style);
+		return;
+	}
+	let (start, printed) = unicode::truncate(text, line.width - 1);
+	buffer.print(line.x, line.y, start, printed, style);
+	buffer.set(line.x + printed, line.y, '…', style);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+	use std::rc::Rc;
+	use crate::backend::TestBackend;
+	use crate::components::popup::Popup;
+	use crate::components::table::{Table, TableTrait};
+	use crate::components::{Container, Layout, Position, Size};
+
+	/// Returns a table of a single column, with a line of data per text.
+	fn table(header: &str, rows: &[&str]) -> Table {
+		let rows = rows.iter().map(|row| vec![row.to_string()]).collect();
+		Table::new(&[header.to_string()], rows)
+	}
+
+	/// Returns the frame of a tree of elements, rendered from scratch.
+	fn fresh(root: &mut dyn UIElement, width: u16, height: u16) -> Buffer {
+		let mut renderer = Renderer::new(TestBackend::new(width, height));
+		renderer.render(root).unwrap();
+		renderer.backend().buffer().clone()
+	}
+
+	#[test]
+	fn setters_redraw_the_element() {
+		let mut table = table("Name", &["tuim"]);
+		let mut renderer = Renderer::new(TestBackend::new(10, 7));
+		renderer.render(&mut table).unwrap();
+		assert_eq!(renderer.backend().lines()[0], "          ");
+
+		table.set_title("Hello");
+		renderer.render(&mut table).unwrap();
+		assert_eq!(renderer.backend().lines()[0], "Hello     ");
+	}
+
+	#[test]
+	fn titles_and_footers_are_aligned_and_cut() {
+		let mut table = table("Name", &["tuim"]);
+		table.set_title("Hosts");
+		table.set_title_align(Align::Right);
+		table.set_footer("A long footer");
+		let mut renderer = Renderer::new(TestBackend::new(10, 7));
+		renderer.render(&mut table).unwrap();
+		assert_eq!(renderer.backend().lines()[0], "     Hosts");
+		assert_eq!(renderer.backend().lines()[6], "A long fo…");
+
+		table.set_footer("end");
+		table.set_footer_align(Align::Center);
+		renderer.render(&mut table).unwrap();
+		assert_eq!(renderer.backend().lines()[6], "   end    ");
+	}
+
+	/// An element counting how many times it's rendered.
+	struct Counter {
+		/// The text drawn by the element.
+		text: &'static str,
+		/// The number of renders, shared with the test.
+		renders: Rc<Cell<usize>>,
+		/// Whether the element has been updated.
+		updated: bool,
+	}
+
+	impl Counter {
+		/// Returns a new Counter, and its number of renders.
+		fn boxed(text: &'static str) -> (Box<dyn UIElement>, Rc<Cell<usize>>) {
+			let renders = Rc::new(Cell::new(0));
+			(Box::new(Self { text, renders: renders.clone(), updated: true }), renders)
+		}
+	}
+
+	impl UIElement for Counter {
+		fn set_z_index(&mut self, _z_index: u8) {}
+		fn set_title(&mut self, _title: &str) {}
+		fn set_title_align(&mut self, _align: Align) {}
+		fn set_footer(&mut self, _footer: &str) {}
+		fn set_footer_align(&mut self, _align: Align) {}
+		fn set_position(&mut self, _position: Position) {}
+		fn set_offset(&mut self, _x: u16, _y: u16) {}
+		fn width(&self) -> Size {
+			Size::Auto
+		}
+		fn set_width(&mut self, _width: Size) {}
+		fn height(&self) -> Size {
+			Size::Chars(1)
+		}
+		fn set_height(&mut self, _height: Size) {}
+		fn updated(&self) -> bool {
+			self.updated
+		}
+		fn set_updated(&mut self, updated: bool) {
+			self.updated = updated;
+		}
+		fn set_border(&mut self, _border: crate::border::BorderSet) {}
+		fn set_border_sides(&mut self, _sides: Sides<bool>) {}
+		fn set_padding(&mut self, _padding: Sides<u16>) {}
+		fn set_margin(&mut self, _margin: Sides<u16>) {}
+		fn set_style(&mut self, _style: Style) {}
+		fn set_border_style(&mut self, _style: Style) {}
+		fn set_title_style(&mut self, _style: Style) {}
+
+		fn render(&self, area: Rect, buffer: &mut Buffer) {
+			self.renders.set(self.renders.get() + 1);
+			buffer.print(area.x, area.y, self.text, area.width, Style::default());
+		}
+	}
+
+	#[test]
+	fn untouched_siblings_are_not_rendered_again() {
+		let (first, first_renders) = Counter::boxed("first");
+		let (second, second_renders) = Counter::boxed("second");
+		let mut root = Container::new(vec![first, second], Layout::Vertical);
+		root.set_style(Style::new().background(crate::style::Color::Blue));
+		let mut renderer = Renderer::new(TestBackend::new(10, 4));
+		renderer.render(&mut root).unwrap();
+		assert_eq!((first_renders.get(), second_renders.get()), (1, 1));
+
+		renderer.render(&mut root).unwrap();
+		assert_eq!((first_renders.get(), second_renders.get()), (1, 1));
+
+		root.children_mut()[0].set_updated(true);
+		renderer.render(&mut root).unwrap();
+		assert_eq!((first_renders.get(), second_renders.get()), (2, 1));
+		assert_eq!(renderer.backend().buffer(), &fresh(&mut root, 10, 4));
+	}
+
+	#[test]
+	fn inactive_tabs_are_not_drawn() {
+		let mut hidden = table("HIDDEN", &[]);
+		hidden.set_position(Position::Absolute);
+		let tab = Container::new(vec![Box::new(hidden)], Layout::Vertical);
+		let mut tabs = Container::new(vec![Box::new(table("Shown", &[])), Box::new(tab)], Layout::Tabbed);
+		let mut renderer = Renderer::new(TestBackend::new(20, 6));
+		let contains = |renderer: &Renderer<TestBackend>, text: &str| {
+			renderer.backend().lines().iter().any(|line| line.contains(text))
+		};
+
+		renderer.render(&mut tabs).unwrap();
+		assert!(contains(&renderer, "Shown"));
+		assert!(!contains(&renderer, "HIDDEN"));
+
+		tabs.set_active_tab(1);
+		renderer.render(&mut tabs).unwrap();
+		assert!(!contains(&renderer, "Shown"));
+		assert!(contains(&renderer, "HIDDEN"));
+	}
+
+	#[test]
+	fn popups_are_composited_over_the_tree() {
+		let mut popup = Popup::new(Box::new(table("Popup", &[])));
+		popup.set_dim(true);
+		let below = table("Below", &["a", "b", "c", "d"]);
+		let mut root = Container::new(vec![Box::new(below), Box::new(popup)], Layout::Vertical);
+		let mut renderer = Renderer::new(TestBackend::new(20, 8));
+		renderer.render(&mut root).unwrap();
+		assert_eq!(&renderer.backend().lines()[2][5..15], "+--------+");
+		assert!(renderer.backend().buffer().get(0, 0).unwrap().style.dim);
+
+		root.children_mut()[0].set_title("Again");
+		renderer.render(&mut root).unwrap();
+		assert!(renderer.backend().lines()[0].starts_with("Again"));
+		assert_eq!(&renderer.backend().lines()[2][5..15], "+--------+");
+		assert_eq!(renderer.backend().buffer(), &fresh(&mut root, 20, 8));
+	}
+
+	#[test]
+	fn raising_an_element_composites_it_again() {
+		let overlapping = |header: &str, x: u16| -> Box<dyn UIElement> {
+			let mut table = table(header, &[]);
+			table.set_position(Position::Absolute);
+			table.set_offset(x, 0);
+			table.set_width(crate::components::Size::Chars(6));
+			Box::new(table)
+		};
+		let below = Container::new(vec![overlapping("AAAA", 0)], Layout::Vertical);
+		let mut root = Container::new(vec![Box::new(below), overlapping("BBBB", 2)], Layout::Vertical);
+		let mut renderer = Renderer::new(TestBackend::new(10, 3));
+		renderer.render(&mut root).unwrap();
+		assert_eq!(renderer.backend().lines()[1], " AABBBB   ");
+
+		// Raise the container, without marking it as updated.
+		root.children_mut()[0].set_z_index(1);
+		root.children_mut()[0].set_updated(false);
+		renderer.render(&mut root).unwrap();
+		assert!(renderer.backend().lines()[1].starts_with(" AAAA"));
+		assert_eq!(renderer.backend().buffer(), &fresh(&mut root, 10, 3));
+
+		root.children_mut()[1].set_z_index(2);
+		renderer.render(&mut root).unwrap();
+		assert_eq!(renderer.backend().lines()[1], " AABBBB   ");
+		assert_eq!(renderer.backend().buffer(), &fresh(&mut root, 10, 3));
+	}
+
+	#[test]
+	fn children_are_painted_over_the_style_of_their_container() {
+		let blue = Style::new().background(crate::style::Color::Blue);
+		let mut root = Container::new(vec![Box::new(table("Name", &["tuim"]))], Layout::Vertical);
+		root.set_style(blue);
+		let mut renderer = Renderer::new(TestBackend::new(10, 5));
+		let background = |renderer: &Renderer<TestBackend>| renderer.backend().buffer().get(9, 4).unwrap().style;
+
+		renderer.render(&mut root).unwrap();
+		assert_eq!(background(&renderer), blue);
+
+		root.children_mut()[0].set_title("Hello");
+		renderer.render(&mut root).unwrap();
+		root.children_mut()[0].set_title("Hi");
+		renderer.render(&mut root).unwrap();
+		assert!(renderer.backend().lines()[0].starts_with("Hi "));
+		assert_eq!(background(&renderer), blue);
+		assert_eq!(renderer.backend().buffer(), &fresh(&mut root, 10, 5));
+	}
+}
@@ -0,0 +1,444 @@
+//! The style module describes how the text is displayed.
+
+use std::env;
+
+/// A color of the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+	/// Black, color 0 of the 16 colors.
+	Black,
+	/// Red, color 1 of the 16 colors.
+	Red,
+	/// Green, color 2 of the 16 colors.
+	Green,
+	/// Yellow, color 3 of the 16 colors.
+	Yellow,
+	/// Blue, color 4 of the 16 colors.
+	Blue,
+	/// Magenta, color 5 of the 16 colors.
+	Magenta,
+	/// Cyan, color 6 of the 16 colors.
+	Cyan,
+	/// White, color 7 of the 16 colors.
+	White,
+	/// Bright black, or grey, color 8 of the 16 colors.
+	BrightBlack,
+	/// Bright red, color 9 of the 16 colors.
+	BrightRed,
+	/// Bright green, color 10 of the 16 colors.
+	BrightGreen,
+	/// Bright yellow, color 11 of the 16 colors.
+	BrightYellow,
+	/// Bright blue, color 12 of the 16 colors.
+	BrightBlue,
+	/// Bright magenta, color 13 of the 16 colors.
+	BrightMagenta,
+	/// Bright cyan, color 14 of the 16 colors.
+	BrightCyan,
+	/// Bright white, color 15 of the 16 colors.
+	BrightWhite,
+	/// One of the 256 colors: the 16 colors, a 6x6x6 cube, then 24 greys.
+	Indexed(u8),
+	/// A color given by its red, green and blue components.
+	Rgb(u8, u8, u8),
+}
+
+/// The 16 colors, in order.
+const ANSI_COLORS: [Color; 16] = [
+	Color::Black, Color::Red, Color::Green, Color::Yellow,
+	Color::Blue, Color::Magenta, Color::Cyan, Color::White,
+	Color::BrightBlack, Color::BrightRed, Color::BrightGreen, Color::BrightYellow,
+	Color::BrightBlue, Color::BrightMagenta, Color::BrightCyan, Color::BrightWhite,
+];
+
+/// The usual components of the 16 colors, used to approach the others.
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+	(0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+	(0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+	(127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+	(92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+/// The levels of the components in the 6x6x6 cube of the 256 colors.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+	/// Returns the index of the color among the 16 colors, if it's one.
+	fn ansi_index(&self) -> Option<u8> {
+		ANSI_COLORS.iter().position(|color| color == self).map(|index| index as u8)
+	}
+
+	/// Parses a color from its name, e.g. red or bright-red, or from its
+	/// components in hexadecimal, e.g. #ff8700.
+	///
+	/// The names ignore the case, and the words may be separated by -, _ or
+	/// a space. Returns None when the text isn't a color.
+	pub fn parse(text: &str) -> Option<Color> {
+		if let Some(hexadecimal) = text.strip_prefix('#') {
+			if hexadecimal.len() != 6 || !hexadecimal.is_ascii() {
+				return None;
+			}
+			let component = |range| u8::from_str_radix(&hexadecimal[range], 16).ok();
+			return Some(Color::Rgb(component(0..2)?, component(2..4)?, component(4..6)?));
+		}
+		let name: String = text.chars()
+			.filter(|c| !matches!(c, '-' | '_' | ' '))
+			.map(|c| c.to_ascii_lowercase())
+			.collect();
+		let (bright, base) = match name.strip_prefix("bright") {
+			Some(base) => (true, base),
+			None => (false, name.as_str()),
+		};
+		let index = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"]
+			.iter()
+			.position(|color| *color == base)?;
+		Some(ANSI_COLORS[index + if bright { 8 } else { 0 }])
+	}
+
+	/// Returns the red, green and blue components of the color.
+	pub fn rgb(&self) -> (u8, u8, u8) {
+		match *self {
+			Color::Rgb(red, green, blue) => (red, green, blue),
+			Color::Indexed(index) if index < 16 => ANSI_RGB[index as usize],
+			Color::Indexed(index) if index < 232 => {
+				let index = index - 16;
+				(
+					CUBE_LEVELS[index as usize / 36],
+					CUBE_LEVELS[index as usize / 6 % 6],
+					CUBE_LEVELS[index as usize % 6],
+				)
+			},
+			Color::Indexed(index) => {
+				let grey = 8 + (index - 232) * 10;
+				(grey, grey, grey)
+			},
+			named => ANSI_RGB[named.ansi_index().unwrap_or(0) as usize],
+		}
+	}
+
+	/// Returns the closest color the terminal can display.
+	///
+	/// Returns None when the terminal can't display colors.
+	pub fn downgrade(&self, depth: ColorDepth) -> Option<Color> {
+		match (depth, *self) {
+			(ColorDepth::NoColor, _) => None,
+			(ColorDepth::TrueColor, color) => Some(color),
+			(ColorDepth::Ansi256, Color::Rgb(red, green, blue)) => Some(Color::Indexed(closest_indexed(red, green, blue))),
+			(ColorDepth::Ansi256, color) => Some(color),
+			(ColorDepth::Ansi16, Color::Indexed(index)) if index < 16 => Some(ANSI_COLORS[index as usize]),
+			(ColorDepth::Ansi16, color @ (Color::Indexed(_) | Color::Rgb(..))) => {
+				let (red, green, blue) = color.rgb();
+				Some(ANSI_COLORS[closest_ansi(red, green, blue)])
+			},
+			(ColorDepth::Ansi16, color) => Some(color),
+		}
+	}
+
+	/// Returns the SGR parameters selecting the color.
+	///
+	/// # Parameters
+	/// - background: whether the color is the background one.
+	fn sgr(&self, background: bool) -> String {
+		let offset = if background { 10 } else { 0 };
+		match self {
+			Color::Indexed(index) => format!("{};5;{}", 38 + offset, index),
+			Color::Rgb(red, green, blue) => format!("{};2;{};{};{}", 38 + offset, red, green, blue),
+			named => {
+				let index = named.ansi_index().unwrap_or(0);
+				let base = if index < 8 { 30 } else { 90 - 8 };
+				(base + offset + index as u16).to_string()
+			},
+		}
+	}
+}
+
+/// Returns the squared distance between two colors.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+	let square = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+	square(r1, r2) + square(g1, g2) + square(b1, b2)
+}
+
+/// Returns the index of the closest of the 16 colors.
+fn closest_ansi(red: u8, green: u8, blue: u8) -> usize {
+	(0..ANSI_RGB.len())
+		.min_by_key(|index| distance(ANSI_RGB[*index], (red, green, blue)))
+		.unwrap_or(0)
+}
+
+/// Returns the index of the closest of the 256 colors, in the cube or
+/// among the greys.
+fn closest_indexed(red: u8, green: u8, blue: u8) -> u8 {
+	let level = |component: u8| {
+		(0..CUBE_LEVELS.len())
+			.min_by_key(|index| (CUBE_LEVELS[*index] as i32 - component as i32).abs())
+			.unwrap_or(0) as u8
+	};
+	let cube = 16 + 36 * level(red) + 6 * level(green) + level(blue);
+	let average = (red as u16 + green as u16 + blue as u16) / 3;
+	let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+	let target = (red, green, blue);
+	if distance(Color::Indexed(grey).rgb(), target) < distance(Color::Indexed(cube).rgb(), target) {
+		grey
+	} else {
+		cube
+	}
+}
+
+/// The colors a terminal can display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+	/// No color, only the attributes.
+	NoColor,
+	/// The 16 colors.
+	Ansi16,
+	/// The 256 colors.
+	Ansi256,
+	/// Any color, given by its components.
+	TrueColor,
+}
+
+impl ColorDepth {
+	/// Guesses the colors of the terminal from the environment.
+	///
+	/// NO_COLOR disables the colors, COLORTERM tells whether any color
+	/// can be displayed, and a TERM ending with 256color allows the 256
+	/// colors. Otherwise the 16 colors are used.
+	pub fn detect() -> Self {
+		if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+			return ColorDepth::NoColor;
+		}
+		let colorterm = env::var("COLORTERM").unwrap_or_default();
+		if colorterm == "truecolor" || colorterm == "24bit" {
+			return ColorDepth::TrueColor;
+		}
+		match env::var("TERM").unwrap_or_default().as_str() {
+			"dumb" => ColorDepth::NoColor,
+			term if term.contains("256color") => ColorDepth::Ansi256,
+			_ => ColorDepth::Ansi16,
+		}
+	}
+}
+
+/// The colors and the attributes applied to a text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+	/// The color of the text, or the default one.
+	pub foreground: Option<Color>,
+	/// The color behind the text, or the default one.
+	pub background: Option<Color>,
+	/// Whether the text is bold.
+	pub bold: bool,
+	/// Whether the text is dimmed.
+	pub dim: bool,
+	/// Whether the text is in italic.
+	pub italic: bool,
+	/// Whether the text is underlined.
+	pub underline: bool,
+	/// Whether the text blinks.
+	pub blink: bool,
+	/// Whether the foreground and background colors are swapped.
+	pub reverse: bool,
+	/// Whether the text is hidden.
+	pub hidden: bool,
+	/// Whether the text is crossed out.
+	pub strikethrough: bool,
+}
+
+impl Style {
+	/// Creates a new Style, without any color nor attribute.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the style with the given color of the text.
+	pub fn foreground(mut self, color: Color) -> Self {
+		self.foreground = Some(color);
+		self
+	}
+
+	/// Returns the style with the given color behind the text.
+	pub fn background(mut self, color: Color) -> Self {
+		self.background = Some(color);
+		self
+	}
+
+	/// Returns the style with the bold attribute.
+	pub fn bold(mut self) -> Self {
+		self.bold = true;
+		self
+	}
+
+	/// Returns the style with the dim attribute.
+	pub fn dim(mut self) -> Self {
+		self.dim = true;
+		self
+	}
+
+	/// Returns the style with the italic attribute.
+	pub fn italic(mut self) -> Self {
+		self.italic = true;
+		self
+	}
+
+	/// Returns the style with the underline attribute.
+	pub fn underline(mut self) -> Self {
+		self.underline = true;
+		self
+	}
+
+	/// Returns the style with the blink attribute.
+	pub fn blink(mut self) -> Self {
+		self.blink = true;
+		self
+	}
+
+	/// Returns the style with the reverse attribute.
+	pub fn reverse(mut self) -> Self {
+		self.reverse = true;
+		self
+	}
+
+	/// Returns the style with the hidden attribute.
+	pub fn hidden(mut self) -> Self {
+		self.hidden = true;
+		self
+	}
+
+	/// Returns the style with the strikethrough attribute.
+	pub fn strikethrough(mut self) -> Self {
+		self.strikethrough = true;
+		self
+	}
+
+	/// Returns the style with another one applied over it: the colors of
+	/// the other style replace these ones, and the attributes are added.
+	pub fn patch(self, other: Style) -> Self {
+		Self {
+			foreground: other.foreground.or(self.foreground),
+			background: other.background.or(self.background),
+			bold: self.bold || other.bold,
+			dim: self.dim || other.dim,
+			italic: self.italic || other.italic,
+			underline: self.underline || other.underline,
+			blink: self.blink || other.blink,
+			reverse: self.reverse || other.reverse,
+			hidden: self.hidden || other.hidden,
+			strikethrough: self.strikethrough || other.strikethrough,
+		}
+	}
+
+	/// Returns the ANSI escape sequence selecting the style, with any color.
+	///
+	/// The sequence resets the previous attributes first.
+	pub fn sgr(&self) -> String {
+		self.sgr_for(ColorDepth::TrueColor)
+	}
+
+	/// Returns the ANSI escape sequence selecting the style, with the
+	/// colors replaced by the closest ones the terminal can display.
+	///
+	/// The sequence resets the previous attributes first.
+	pub fn sgr_for(&self, depth: ColorDepth) -> String {
+		let mut codes = vec!["0".to_string()];
+		let attributes = [
+			(self.bold, "1"),
+			(self.dim, "2"),
+			(self.italic, "3"),
+			(self.underline, "4"),
+			(self.blink, "5"),
+			(self.reverse, "7"),
+			(self.hidden, "8"),
+			(self.strikethrough, "9"),
+		];
+		codes.extend(attributes.iter()
+			.filter(|(enabled, _)| *enabled)
+			.map(|(_, code)| code.to_string()));
+		if let Some(color) = self.foreground.and_then(|color| color.downgrade(depth)) {
+			codes.push(color.sgr(false));
+		}
+		if let Some(color) = self.background.and_then(|color| color.downgrade(depth)) {
+			codes.push(color.sgr(true));
+		}
+		format!("\x1b[{}m", codes.join(";"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parsing() {
+		assert_eq!(Color::parse("red"), Some(Color::Red));
+		assert_eq!(Color::parse("Bright-Red"), Some(Color::BrightRed));
+		assert_eq!(Color::parse("bright_blue"), Some(Color::BrightBlue));
+		assert_eq!(Color::parse("BRIGHT WHITE"), Some(Color::BrightWhite));
+		assert_eq!(Color::parse("#ff8700"), Some(Color::Rgb(255, 135, 0)));
+		assert_eq!(Color::parse("#FF8700"), Some(Color::Rgb(255, 135, 0)));
+		for text in ["", "purple", "bright", "brightbrightred", "#ff87", "#ff87000", "#gg0000", "#ééé", "ff8700"] {
+			assert_eq!(Color::parse(text), None, "{}", text);
+		}
+	}
+
+	#[test]
+	fn components() {
+		assert_eq!(Color::Red.rgb(), (205, 0, 0));
+		assert_eq!(Color::Indexed(1).rgb(), (205, 0, 0));
+		assert_eq!(Color::Indexed(16).rgb(), (0, 0, 0));
+		assert_eq!(Color::Indexed(67).rgb(), (95, 135, 175));
+		assert_eq!(Color::Indexed(231).rgb(), (255, 255, 255));
+		assert_eq!(Color::Indexed(232).rgb(), (8, 8, 8));
+		assert_eq!(Color::Indexed(255).rgb(), (238, 238, 238));
+	}
+
+	#[test]
+	fn downgrade_to_256_colors() {
+		let downgrade = |red, green, blue| Color::Rgb(red, green, blue).downgrade(ColorDepth::Ansi256);
+		// The corners of the cube.
+		assert_eq!(downgrade(0, 0, 0), Some(Color::Indexed(16)));
+		assert_eq!(downgrade(255, 0, 0), Some(Color::Indexed(196)));
+		assert_eq!(downgrade(255, 255, 255), Some(Color::Indexed(231)));
+		assert_eq!(downgrade(95, 135, 175), Some(Color::Indexed(67)));
+		// The greys, from the darkest to the lightest.
+		assert_eq!(downgrade(8, 8, 8), Some(Color::Indexed(232)));
+		assert_eq!(downgrade(128, 128, 128), Some(Color::Indexed(244)));
+		assert_eq!(downgrade(238, 238, 238), Some(Color::Indexed(255)));
+		// Closer to white than to the lightest grey.
+		assert_eq!(downgrade(250, 250, 250), Some(Color::Indexed(231)));
+
+		assert_eq!(Color::Indexed(100).downgrade(ColorDepth::Ansi256), Some(Color::Indexed(100)));
+		assert_eq!(Color::Cyan.downgrade(ColorDepth::Ansi256), Some(Color::Cyan));
+	}
+
+	#[test]
+	fn downgrade_to_16_colors() {
+		let downgrade = |color: Color| color.downgrade(ColorDepth::Ansi16);
+		assert_eq!(downgrade(Color::Indexed(9)), Some(Color::BrightRed));
+		assert_eq!(downgrade(Color::Indexed(196)), Some(Color::BrightRed));
+		assert_eq!(downgrade(Color::Indexed(244)), Some(Color::BrightBlack));
+		assert_eq!(downgrade(Color::Rgb(0, 0, 0)), Some(Color::Black));
+		assert_eq!(downgrade(Color::Rgb(240, 240, 240)), Some(Color::White));
+		assert_eq!(downgrade(Color::Rgb(250, 250, 250)), Some(Color::BrightWhite));
+		assert_eq!(downgrade(Color::Magenta), Some(Color::Magenta));
+
+		assert_eq!(Color::Rgb(1, 2, 3).downgrade(ColorDepth::TrueColor), Some(Color::Rgb(1, 2, 3)));
+		assert_eq!(Color::Red.downgrade(ColorDepth::NoColor), None);
+	}
+
+	#[test]
+	fn sgr_sequences() {
+		assert_eq!(Style::default().sgr(), "\x1b[0m");
+		let style = Style::new().bold().underline().foreground(Color::Red).background(Color::BrightBlue);
+		assert_eq!(style.sgr(), "\x1b[0;1;4;31;104m");
+		let all = Style::new().bold().dim().italic().underline().blink().reverse().hidden().strikethrough();
+		assert_eq!(all.sgr(), "\x1b[0;1;2;3;4;5;7;8;9m");
+		assert_eq!(Style::new().foreground(Color::BrightBlack).background(Color::Black).sgr(), "\x1b[0;90;40m");
+		assert_eq!(Style::new().background(Color::BrightWhite).sgr(), "\x1b[0;107m");
+
+		let style = Style::new().foreground(Color::Rgb(255, 0, 0)).background(Color::Indexed(67));
+		assert_eq!(style.sgr(), "\x1b[0;38;2;255;0;0;48;5;67m");
+		assert_eq!(style.sgr_for(ColorDepth::Ansi256), "\x1b[0;38;5;196;48;5;67m");
+		assert_eq!(style.sgr_for(ColorDepth::Ansi16), "\x1b[0;91;100m");
+		assert_eq!(style.bold().sgr_for(ColorDepth::NoColor), "\x1b[0;1m");
+	}
+}
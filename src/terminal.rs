@@ -0,0 +1,345 @@
+//! The terminal module sets up the terminal and runs the event loop.
+
+use std::io::{self, Stdout, Write};
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+use crate::backend::TerminalBackend;
+use crate::components::UIElement;
+use crate::event::{self, Event, MouseEvent, MouseKind, Parser};
+use crate::focus::Focus;
+use crate::render::Renderer;
+
+/// The escape sequences sent when entering the UI: alternate screen,
+/// hidden cursor, and mouse reporting with the SGR encoding.
+const ENTER: &str = "\x1b[?1049h\x1b[?25l\x1b[?1000h\x1b[?1002h\x1b[?1006h";
+/// The escape sequences sent when leaving the UI, undoing ENTER.
+const LEAVE: &str = "\x1b[?1006l\x1b[?1002l\x1b[?1000l\x1b[0m\x1b[?25h\x1b[?1049l";
+/// How long to wait for the rest of an escape sequence.
+const ESCAPE_DELAY: Duration = Duration::from_millis(30);
+
+/// The terminal settings to restore, shared with the panic hook.
+static SAVED_SETTINGS: Mutex<Option<libc::termios>> = Mutex::new(None);
+/// The end of the pipe written to when the terminal is resized, shared
+/// with the SIGWINCH handler. Negative until the handler is installed.
+static RESIZE_WRITER: AtomicI32 = AtomicI32::new(-1);
+
+/// Puts the terminal in raw mode and on the alternate screen, for
+/// as long as it's alive.
+///
+/// The terminal is restored when the value is dropped, and when the
+/// program panics.
+pub struct Terminal {
+	/// Whether the terminal has already been restored.
+	restored: bool,
+}
+
+impl Terminal {
+	/// Sets the terminal up.
+	///
+	/// The terminal must be the standard input. When the setup fails
+	/// halfway, what has been changed is restored.
+	pub fn new() -> io::Result<Self> {
+		// SAFETY: termios is plain data, filled by tcgetattr.
+		let mut settings: libc::termios = unsafe { std::mem::zeroed() };
+		// SAFETY: tcgetattr only writes a termios at the given address.
+		if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut settings) } != 0 {
+			return Err(io::Error::last_os_error());
+		}
+		*SAVED_SETTINGS.lock().unwrap_or_else(|error| error.into_inner()) = Some(settings);
+		install_panic_hook();
+		// Dropped on error, restoring the terminal.
+		let terminal = Self { restored: false };
+		let mut raw = settings;
+		// SAFETY: cfmakeraw only changes the flags of the given termios.
+		unsafe { libc::cfmakeraw(&mut raw) };
+		set_settings(&raw)?;
+		let mut stdout = io::stdout();
+		write!(stdout, "{}", ENTER)?;
+		stdout.flush()?;
+		Ok(terminal)
+	}
+
+	/// Returns the width and the height of the terminal.
+	pub fn size() -> io::Result<(u16, u16)> {
+		// SAFETY: winsize is plain data, filled by the ioctl.
+		let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+		// SAFETY: TIOCGWINSZ only writes a winsize at the given address.
+		if unsafe { libc::ioctl(libc::STDIN_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+			return Err(io::Error::last_os_error());
+		}
+		if size.ws_col == 0 || size.ws_row == 0 {
+			return Err(io::Error::other("the terminal has no size"));
+		}
+		Ok((size.ws_col, size.ws_row))
+	}
+
+	/// Restores the terminal as it was before.
+	pub fn restore(&mut self) -> io::Result<()> {
+		if !self.restored {
+			self.restored = true;
+			restore_settings()?;
+		}
+		Ok(())
+	}
+}
+
+impl Drop for Terminal {
+	/// Restores the terminal.
+	fn drop(&mut self) {
+		let _ = self.restore();
+	}
+}
+
+/// Applies settings to the terminal of the standard input.
+fn set_settings(settings: &libc::termios) -> io::Result<()> {
+	// SAFETY: tcsetattr only reads the given termios.
+	if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, settings) } != 0 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(())
+}
+
+/// Leaves the alternate screen and restores the saved terminal settings.
+///
+/// The settings are restored even when the alternate screen can't be left.
+fn restore_settings() -> io::Result<()> {
+	let saved = SAVED_SETTINGS.lock().unwrap_or_else(|error| error.into_inner()).take();
+	if let Some(settings) = saved {
+		let mut stdout = io::stdout();
+		let left = write!(stdout, "{}", LEAVE).and_then(|_| stdout.flush());
+		set_settings(&settings)?;
+		left?;
+	}
+	Ok(())
+}
+
+/// Makes sure the terminal is restored before the panic message is printed.
+fn install_panic_hook() {
+	static INSTALL: Once = Once::new();
+	INSTALL.call_once(|| {
+		let previous = std::panic::take_hook();
+		std::panic::set_hook(Box::new(move |info| {
+			let _ = restore_settings();
+			previous(info);
+		}));
+	});
+}
+
+/// Writes to the resize pipe, to wake up the event loop.
+extern "C" fn on_resize(_signal: libc::c_int) {
+	let writer = RESIZE_WRITER.load(Ordering::Relaxed);
+	if writer >= 0 {
+		// SAFETY: write is async-signal-safe, and a full pipe is fine
+		// since a single byte is enough to wake the event loop up.
+		unsafe { libc::write(writer, [0u8].as_ptr().cast(), 1) };
+	}
+}
+
+/// Installs the SIGWINCH handler, the first time it's called.
+///
+/// Returns the end of the pipe to read from, which receives a byte each
+/// time the terminal is resized.
+fn watch_resize() -> io::Result<RawFd> {
+	static READER: Mutex<Option<RawFd>> = Mutex::new(None);
+	let mut reader = READER.lock().unwrap_or_else(|error| error.into_inner());
+	if let Some(fd) = *reader {
+		return Ok(fd);
+	}
+	let mut fds: [RawFd; 2] = [-1; 2];
+	// SAFETY: pipe writes two file descriptors in the array.
+	if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+		return Err(io::Error::last_os_error());
+	}
+	for fd in fds {
+		// SAFETY: the file descriptors have just been opened.
+		unsafe {
+			libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK);
+			libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+		}
+	}
+	RESIZE_WRITER.store(fds[1], Ordering::Relaxed);
+	// SAFETY: sigaction is plain data, the handler only calls write.
+	unsafe {
+		let mut action: libc::sigaction = std::mem::zeroed();
+		action.sa_sigaction = on_resize as extern "C" fn(libc::c_int) as libc::sighandler_t;
+		action.sa_flags = libc::SA_RESTART;
+		libc::sigemptyset(&mut action.sa_mask);
+		if libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut()) != 0 {
+			return Err(io::Error::last_os_error());
+		}
+	}
+	*reader = Some(fds[0]);
+	Ok(fds[0])
+}
+
+/// Reads the events of the terminal.
+///
+/// The standard input is only read while waiting for an event, on the
+/// thread calling `poll`, so nothing is consumed once the events aren't
+/// polled anymore. The resizes are signaled by SIGWINCH.
+pub struct Events {
+	/// Decodes the bytes.
+	parser: Parser,
+	/// The events decoded, not returned yet.
+	queue: Vec<Event>,
+	/// The last known size of the terminal.
+	size: (u16, u16),
+	/// The pipe receiving a byte when the terminal is resized.
+	resize: RawFd,
+}
+
+impl Events {
+	/// Starts watching the resizes of the terminal.
+	pub fn new() -> io::Result<Self> {
+		Ok(Self {
+			parser: Parser::new(),
+			queue: Vec::new(),
+			size: Terminal::size()?,
+			resize: watch_resize()?,
+		})
+	}
+
+	/// Waits for the next event.
+	///
+	/// Returns None when nothing happened before the timeout.
+	pub fn poll(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			if !self.queue.is_empty() {
+				return Ok(Some(self.queue.remove(0)));
+			}
+			let now = Instant::now();
+			if now >= deadline {
+				return Ok(None);
+			}
+			let mut wait = deadline - now;
+			if self.parser.has_pending() {
+				wait = wait.min(ESCAPE_DELAY);
+			}
+			let mut fds = [
+				libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 },
+				libc::pollfd { fd: self.resize, events: libc::POLLIN, revents: 0 },
+			];
+			let milliseconds = wait.as_millis().clamp(1, libc::c_int::MAX as u128) as libc::c_int;
+			// SAFETY: the file descriptors are given with their number.
+			let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, milliseconds) };
+			if ready < 0 {
+				let error = io::Error::last_os_error();
+				if error.kind() == io::ErrorKind::Interrupted {
+					continue;
+				}
+				return Err(error);
+			}
+			if ready == 0 {
+				self.queue.extend(self.parser.flush());
+				continue;
+			}
+			if fds[1].revents != 0 {
+				self.drain_resize();
+				let size = Terminal::size()?;
+				if size != self.size {
+					self.size = size;
+					self.queue.push(Event::Resize(size.0, size.1));
+				}
+			}
+			if fds[0].revents != 0 {
+				let bytes = read_stdin()?;
+				self.queue.extend(self.parser.feed(&bytes));
+			}
+		}
+	}
+
+	/// Empties the resize pipe, several resizes being handled at once.
+	fn drain_resize(&self) {
+		let mut buffer = [0u8; 64];
+		// SAFETY: the pipe is non blocking, and read writes at most the
+		// size of the buffer.
+		while unsafe { libc::read(self.resize, buffer.as_mut_ptr().cast(), buffer.len()) } > 0 {}
+	}
+}
+
+/// Reads the bytes available on the standard input.
+///
+/// The file descriptor is read directly: the buffer of io::Stdin could
+/// keep bytes that poll would not report.
+fn read_stdin() -> io::Result<Vec<u8>> {
+	let mut buffer = [0u8; 1024];
+	loop {
+		// SAFETY: read writes at most the size of the buffer.
+		let read = unsafe { libc::read(libc::STDIN_FILENO, buffer.as_mut_ptr().cast(), buffer.len()) };
+		match read {
+			0 => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "standard input closed")),
+			read if read > 0 => return Ok(buffer[..read as usize].to_vec()),
+			_ => {
+				let error = io::Error::last_os_error();
+				if error.kind() != io::ErrorKind::Interrupted {
+					return Err(error);
+				}
+			},
+		}
+	}
+}
+
+/// What the event loop does after an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+	/// Keep running.
+	Continue,
+	/// Stop the event loop.
+	Quit,
+}
+
+/// Runs the UI until the handler asks to quit.
+///
+/// The terminal is set up, the first focusable element is focused and
+/// the root element is drawn. Then each event is delivered to the
+/// elements and to the handler, and the elements are drawn again.
+/// Keys go to the focused element, mouse events to the element under
+/// the mouse, which a click focuses. The terminal is restored when the
+/// loop ends, even on error.
+///
+/// # Parameters
+/// - root: the root of the tree of UI elements.
+/// - handler: called after each event has been delivered to the elements.
+pub fn run<F>(root: &mut dyn UIElement, mut handler: F) -> io::Result<()>
+where
+	F: FnMut(&Event, &mut dyn UIElement) -> Flow,
+{
+	let mut terminal = Terminal::new()?;
+	let mut events = Events::new()?;
+	let (width, height) = Terminal::size()?;
+	let mut renderer: Renderer<TerminalBackend<Stdout>> =
+		Renderer::new(TerminalBackend::new(io::stdout(), width, height));
+	let mut focus = Focus::new();
+	focus.next(root);
+	renderer.render(root)?;
+	loop {
+		let Some(event) = events.poll(Duration::from_millis(100))? else {
+			continue;
+		};
+		if let Event::Resize(width, height) = event {
+			renderer.backend_mut().resize(width, height);
+		}
+		match event {
+			Event::Mouse(MouseEvent { kind, x, y, .. }) => {
+				if let Some(target) = renderer.element_at(x, y) {
+					if let MouseKind::Press(_) = kind {
+						focus.focus_closest(root, &target);
+					}
+					event::dispatch(root, &event, Some(&target));
+				}
+			},
+			_ => {
+				focus.dispatch(root, &event);
+			},
+		}
+		if handler(&event, root) == Flow::Quit {
+			break;
+		}
+		renderer.render(root)?;
+	}
+	terminal.restore()
+}
@@ -0,0 +1,300 @@
+//! The theme module gives the styles and the borders of the UI elements
+//! from their role, e.g. "border" or "table.header".
+//!
+//! A theme is built in the code, or loaded from a TOML or JSON file, or
+//! a YAML file with the yaml feature:
+//!
+//! ```toml
+//! [styles]
+//! base = { foreground = "white", background = 235 }
+//! border = { foreground = "#5f87af" }
+//! title = { foreground = "bright-white", bold = true }
+//! "table.header" = { bold = true, underline = true }
+//! "table.row.alt" = { background = 236 }
+//! "table.selected" = { reverse = true }
+//!
+//! [borders]
+//! border = "rounded"
+//! "popup.border" = "double"
+//! ```
+//!
+//! The common roles are base, the style of the element, border and title.
+//! They can be given for a kind of element only by prefixing them with
+//! the kind: table, popup or container. A Table also uses table.header,
+//! table.row, table.row.alt and table.selected.
+//!
+//! A color is a name such as red or bright-red, an index among the 256
+//! colors, or its components such as #ff8700. A border is the name of a
+//! preset (none, ascii, single, double, rounded or heavy), or a table of
+//! chars overriding the ones of a preset given by the preset key.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::border::BorderSet;
+use crate::components::UIElement;
+use crate::config::Value;
+use crate::style::{Color, Style};
+use crate::Error;
+
+/// The styles and the borders of the UI elements, by role.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+	/// The styles, by role.
+	styles: HashMap<String, Style>,
+	/// The border sets, by role.
+	borders: HashMap<String, BorderSet>,
+}
+
+impl Theme {
+	/// Creates a new Theme, without any role.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// A theme with light texts on a dark background.
+	pub fn dark() -> Self {
+		let mut theme = Self::new();
+		theme.set_style("base", Style::new().foreground(Color::Indexed(252)).background(Color::Indexed(235)));
+		theme.set_style("border", Style::new().foreground(Color::Indexed(240)));
+		theme.set_style("title", Style::new().foreground(Color::BrightWhite).bold());
+		theme.set_style("table.header", Style::new().foreground(Color::BrightCyan).bold());
+		theme.set_style("table.row", Style::new());
+		theme.set_style("table.row.alt", Style::new().background(Color::Indexed(237)));
+		theme.set_style("table.selected", Style::new().foreground(Color::BrightWhite).background(Color::Indexed(24)));
+		theme.set_border("border", BorderSet::ROUNDED);
+		theme.set_border("table.border", BorderSet::SINGLE);
+		theme
+	}
+
+	/// A theme with dark texts on a light background.
+	pub fn light() -> Self {
+		let mut theme = Self::new();
+		theme.set_style("base", Style::new().foreground(Color::Indexed(235)).background(Color::Indexed(255)));
+		theme.set_style("border", Style::new().foreground(Color::Indexed(245)));
+		theme.set_style("title", Style::new().foreground(Color::Black).bold());
+		theme.set_style("table.header", Style::new().foreground(Color::Blue).bold());
+		theme.set_style("table.row", Style::new());
+		theme.set_style("table.row.alt", Style::new().background(Color::Indexed(254)));
+		theme.set_style("table.selected", Style::new().foreground(Color::Black).background(Color::Indexed(153)));
+		theme.set_border("border", BorderSet::ROUNDED);
+		theme.set_border("table.border", BorderSet::SINGLE);
+		theme
+	}
+
+	/// A theme using only the 16 colors, with strong contrasts.
+	pub fn high_contrast() -> Self {
+		let mut theme = Self::new();
+		theme.set_style("base", Style::new().foreground(Color::BrightWhite).background(Color::Black));
+		theme.set_style("border", Style::new().foreground(Color::BrightYellow));
+		theme.set_style("title", Style::new().foreground(Color::BrightYellow).bold().underline());
+		theme.set_style("table.header", Style::new().foreground(Color::BrightWhite).bold().underline());
+		theme.set_style("table.row", Style::new());
+		theme.set_style("table.row.alt", Style::new());
+		theme.set_style("table.selected", Style::new().reverse().bold());
+		theme.set_border("border", BorderSet::HEAVY);
+		theme
+	}
+
+	/// Parses a theme written in TOML.
+	pub fn from_toml(text: &str) -> Result<Self, Error> {
+		Self::from_value(&Value::parse_toml(text)?)
+	}
+
+	/// Parses a theme written in JSON.
+	pub fn from_json(text: &str) -> Result<Self, Error> {
+		Self::from_value(&Value::parse_json(text)?)
+	}
+
+	/// Parses a theme written in YAML.
+	#[cfg(feature = "yaml")]
+	pub fn from_yaml(text: &str) -> Result<Self, Error> {
+		Self::from_value(&Value::parse_yaml(text)?)
+	}
+
+	/// Loads a theme from a file.
+	///
+	/// Files ending with .json are parsed as JSON, the ones ending with
+	/// .yaml or .yml as YAML with the yaml feature, the other ones as TOML.
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+		Self::from_value(&Value::load(path.as_ref())?)
+	}
+
+	/// Builds a theme from the tables styles and borders of a configuration.
+	pub fn from_value(value: &Value) -> Result<Self, Error> {
+		let mut theme = Self::new();
+		let Some(entries) = value.as_table() else {
+			return Err(invalid("", "expected a table"));
+		};
+		for (section, value) in entries {
+			let Some(roles) = value.as_table() else {
+				return Err(invalid(section, "expected a table"));
+			};
+			for (role, value) in roles {
+				let key = format!("{}.{}", section, role);
+				match section.as_str() {
+					"styles" => theme.set_style(role, parse_style(&key, value)?),
+					"borders" => theme.set_border(role, parse_border(&key, value)?),
+					_ => return Err(invalid(section, "unknown section, expected styles or borders")),
+				}
+			}
+		}
+		Ok(theme)
+	}
+
+	/// Returns the style of a role, if the theme has one.
+	pub fn style(&self, role: &str) -> Option<Style> {
+		self.styles.get(role).copied()
+	}
+
+	/// Sets the style of a role.
+	pub fn set_style(&mut self, role: &str, style: Style) {
+		self.styles.insert(role.to_string(), style);
+	}
+
+	/// Returns the border set of a role, if the theme has one.
+	pub fn border(&self, role: &str) -> Option<BorderSet> {
+		self.borders.get(role).copied()
+	}
+
+	/// Sets the border set of a role.
+	pub fn set_border(&mut self, role: &str, border: BorderSet) {
+		self.borders.insert(role.to_string(), border);
+	}
+
+	/// Returns the style of a role for a kind of element: the one given
+	/// for the kind, e.g. table.border, or else the common one.
+	pub fn style_for(&self, kind: &str, role: &str) -> Option<Style> {
+		kind_lookup(&self.styles, kind, role)
+	}
+
+	/// Returns the border set of a role for a kind of element: the one
+	/// given for the kind, e.g. table.border, or else the common one.
+	pub fn border_for(&self, kind: &str, role: &str) -> Option<BorderSet> {
+		kind_lookup(&self.borders, kind, role)
+	}
+
+	/// Applies the theme to an element and all its children.
+	pub fn apply(&self, element: &mut dyn UIElement) {
+		element.apply_theme(self);
+	}
+
+	/// Applies the common roles to a single element: base, border and
+	/// title. The roles missing from the theme are left untouched.
+	///
+	/// # Parameters
+	/// - element: the element to style.
+	/// - kind: the kind of the element, e.g. table, or an empty string.
+	pub fn apply_element<E: UIElement + ?Sized>(&self, element: &mut E, kind: &str) {
+		if let Some(style) = self.style_for(kind, "base") {
+			element.set_style(style);
+		}
+		if let Some(style) = self.style_for(kind, "border") {
+			element.set_border_style(style);
+		}
+		if let Some(style) = self.style_for(kind, "title") {
+			element.set_title_style(style);
+		}
+		if let Some(border) = self.border_for(kind, "border") {
+			element.set_border(border);
+		}
+	}
+}
+
+/// Returns the value of a role prefixed by a kind of element, or else the
+/// value of the role.
+fn kind_lookup<T: Copy>(values: &HashMap<String, T>, kind: &str, role: &str) -> Option<T> {
+	if !kind.is_empty() {
+		if let Some(value) = values.get(&format!("{}.{}", kind, role)) {
+			return Some(*value);
+		}
+	}
+	values.get(role).copied()
+}
+
+/// Returns the error of an invalid value.
+pub(crate) fn invalid(key: &str, message: &str) -> Error {
+	Error::InvalidValue { key: key.to_string(), message: message.to_string() }
+}
+
+/// Builds a style from a table of colors and attributes.
+pub(crate) fn parse_style(key: &str, value: &Value) -> Result<Style, Error> {
+	let Some(entries) = value.as_table() else {
+		return Err(invalid(key, "expected a table of colors and attributes"));
+	};
+	let mut style = Style::new();
+	for (name, value) in entries {
+		let key = format!("{}.{}", key, name);
+		let attribute = match name.as_str() {
+			"foreground" => {
+				style.foreground = Some(parse_color(&key, value)?);
+				continue;
+			},
+			"background" => {
+				style.background = Some(parse_color(&key, value)?);
+				continue;
+			},
+			"bold" => &mut style.bold,
+			"dim" => &mut style.dim,
+			"italic" => &mut style.italic,
+			"underline" => &mut style.underline,
+			"blink" => &mut style.blink,
+			"reverse" => &mut style.reverse,
+			"hidden" => &mut style.hidden,
+			"strikethrough" => &mut style.strikethrough,
+			_ => return Err(invalid(&key, "unknown property")),
+		};
+		*attribute = value.as_bool().ok_or_else(|| invalid(&key, "expected a boolean"))?;
+	}
+	Ok(style)
+}
+
+/// Parses a color: a name, an index among the 256 colors or #rrggbb.
+fn parse_color(key: &str, value: &Value) -> Result<Color, Error> {
+	match value {
+		Value::Integer(index) => u8::try_from(*index)
+			.map(Color::Indexed)
+			.map_err(|_| invalid(key, "expected an index between 0 and 255")),
+		Value::String(name) => Color::parse(name).ok_or_else(|| invalid(key, "unknown color")),
+		_ => Err(invalid(key, "expected a color")),
+	}
+}
+
+/// Parses a border: the name of a preset, or a table of chars overriding
+/// the ones of a preset.
+pub(crate) fn parse_border(key: &str, value: &Value) -> Result<BorderSet, Error> {
+	let preset = |name: &str| BorderSet::preset(name).ok_or_else(|| invalid(key, "unknown border preset"));
+	if let Some(name) = value.as_str() {
+		return preset(name);
+	}
+	let Some(entries) = value.as_table() else {
+		return Err(invalid(key, "expected a preset name or a table of chars"));
+	};
+	let mut border = match value.get("preset") {
+		Some(name) => preset(name.as_str().ok_or_else(|| invalid(key, "expected a preset name"))?)?,
+		None => BorderSet::NONE,
+	};
+	for (name, value) in entries.iter().filter(|(name, _)| name != "preset") {
+		let key = format!("{}.{}", key, name);
+		let piece = match name.as_str() {
+			"horizontal" => &mut border.horizontal,
+			"vertical" => &mut border.vertical,
+			"top_left" => &mut border.top_left,
+			"top_right" => &mut border.top_right,
+			"bottom_left" => &mut border.bottom_left,
+			"bottom_right" => &mut border.bottom_right,
+			"top_tee" => &mut border.top_tee,
+			"bottom_tee" => &mut border.bottom_tee,
+			"left_tee" => &mut border.left_tee,
+			"right_tee" => &mut border.right_tee,
+			"cross" => &mut border.cross,
+			_ => return Err(invalid(&key, "unknown border piece")),
+		};
+		let mut chars = value.as_str().unwrap_or_default().chars();
+		*piece = match (chars.next(), chars.next()) {
+			(Some(c), None) => c,
+			_ => return Err(invalid(&key, "expected a single char")),
+		};
+	}
+	Ok(border)
+}
@@ -0,0 +1,132 @@
+//! The unicode module measures the texts as they are displayed.
+//!
+//! A text is split into graphemes, the units seen as a single char: a
+//! char with its combining marks, an emoji sequence or a flag. Each
+//! grapheme takes 0, 1 or 2 cells of the screen: CJK chars and most
+//! emoji take 2 cells, and are never split in half.
+//!
+//! The graphemes and the widths follow the Unicode data of the
+//! unicode-segmentation and unicode-width crates (UAX #29 and UAX #11).
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Returns the number of cells taken by a char on its own.
+///
+/// The control chars and the combining marks take no cell.
+pub fn char_width(c: char) -> u16 {
+	if c.is_control() {
+		0
+	} else {
+		c.width().unwrap_or(0) as u16
+	}
+}
+
+/// Splits a text into graphemes, with their byte offset.
+///
+/// A grapheme is an extended grapheme cluster: a char followed by its
+/// combining marks and variation selectors, a sequence of emoji joined
+/// by zero width joiners, a pair of regional indicators, or a carriage
+/// return followed by a line feed.
+pub fn graphemes(text: &str) -> impl Iterator<Item = (usize, &str)> {
+	text.grapheme_indices(true)
+}
+
+/// Returns the number of cells taken by a grapheme.
+///
+/// The graphemes with a control char take no cell, the other ones at
+/// most two.
+pub fn grapheme_width(grapheme: &str) -> u16 {
+	if grapheme.chars().any(char::is_control) {
+		0
+	} else {
+		grapheme.width().min(2) as u16
+	}
+}
+
+/// Returns the number of cells taken by a text.
+pub fn width(text: &str) -> u16 {
+	graphemes(text).fold(0, |width, (_, grapheme)| width.saturating_add(grapheme_width(grapheme)))
+}
+
+/// Returns the longest start of a text fitting in a number of cells,
+/// without splitting a grapheme, and its width.
+pub fn truncate(text: &str, max_width: u16) -> (&str, u16) {
+	let mut width: u16 = 0;
+	for (offset, grapheme) in graphemes(text) {
+		let grapheme_width = grapheme_width(grapheme);
+		if width + grapheme_width > max_width {
+			return (&text[..offset], width);
+		}
+		width += grapheme_width;
+	}
+	(text, width)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Returns the graphemes of a text, without their offset.
+	fn split(text: &str) -> Vec<&str> {
+		graphemes(text).map(|(_, grapheme)| grapheme).collect()
+	}
+
+	#[test]
+	fn char_widths() {
+		assert_eq!(char_width('a'), 1);
+		assert_eq!(char_width('é'), 1);
+		assert_eq!(char_width('\t'), 0);
+		assert_eq!(char_width('\u{0301}'), 0);
+		assert_eq!(char_width('\u{200B}'), 0);
+		assert_eq!(char_width('東'), 2);
+		assert_eq!(char_width('한'), 2);
+		assert_eq!(char_width('Ａ'), 2);
+		assert_eq!(char_width('😀'), 2);
+		assert_eq!(char_width('\u{20000}'), 2);
+		assert_eq!(char_width('…'), 1);
+		assert_eq!(char_width('\u{09BC}'), 0);
+		assert_eq!(char_width('\u{0BCD}'), 0);
+	}
+
+	#[test]
+	fn grapheme_segmentation() {
+		assert_eq!(split(""), Vec::<&str>::new());
+		assert_eq!(split("ab"), ["a", "b"]);
+		assert_eq!(split("e\u{0301}x"), ["e\u{0301}", "x"]);
+		assert_eq!(split("\r\n\n"), ["\r\n", "\n"]);
+		assert_eq!(split("👍🏽!"), ["👍🏽", "!"]);
+		assert_eq!(split("👩\u{200D}💻a"), ["👩\u{200D}💻", "a"]);
+		assert_eq!(split("🇫🇷🇯🇵"), ["🇫🇷", "🇯🇵"]);
+		assert_eq!(split("🇫🇷🇯"), ["🇫🇷", "🇯"]);
+		assert_eq!(split("❤\u{FE0F}"), ["❤\u{FE0F}"]);
+		assert_eq!(split("\u{1100}\u{1161}\u{11A8}a"), ["\u{1100}\u{1161}\u{11A8}", "a"]);
+		assert_eq!(split("क\u{094D}\u{0937}"), ["क\u{094D}\u{0937}"]);
+		let offsets: Vec<usize> = graphemes("a東b").map(|(offset, _)| offset).collect();
+		assert_eq!(offsets, [0, 1, 4]);
+	}
+
+	#[test]
+	fn text_widths() {
+		assert_eq!(grapheme_width(""), 0);
+		assert_eq!(grapheme_width("e\u{0301}"), 1);
+		assert_eq!(grapheme_width("❤"), 1);
+		assert_eq!(grapheme_width("❤\u{FE0F}"), 2);
+		assert_eq!(grapheme_width("👩\u{200D}💻"), 2);
+		assert_eq!(grapheme_width("🇫🇷"), 2);
+		assert_eq!(grapheme_width("\u{1100}\u{1161}\u{11A8}"), 2);
+		assert_eq!(grapheme_width("\r\n"), 0);
+		assert_eq!(width("東京 tower"), 10);
+		assert_eq!(width("cafe\u{0301}"), 4);
+	}
+
+	#[test]
+	fn truncation() {
+		assert_eq!(truncate("hello", 3), ("hel", 3));
+		assert_eq!(truncate("hello", 9), ("hello", 5));
+		assert_eq!(truncate("東京", 3), ("東", 2));
+		assert_eq!(truncate("東京", 1), ("", 0));
+		assert_eq!(truncate("e\u{0301}f", 1), ("e\u{0301}", 1));
+		assert_eq!(truncate("👩\u{200D}💻", 1), ("", 0));
+	}
+}